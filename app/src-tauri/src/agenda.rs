@@ -0,0 +1,163 @@
+use chrono::{DateTime, Days, Local, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::{
+  calendar::planning::local_day_bounds,
+  i18n,
+  storage::{StorageError, TodoItem},
+};
+
+/// 默认列出之后 7 天内的近期待办。
+pub const DEFAULT_UPCOMING_DAYS: u32 = 7;
+
+/// 某天的日程：每个未完成待办只归入最靠前的一组。
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Agenda {
+  pub date: String,
+  /// 截止时间早于当天的待办。
+  pub overdue: Vec<TodoItem>,
+  pub due_today: Vec<TodoItem>,
+  pub planned_today: Vec<TodoItem>,
+  /// 之后若干天内截止或计划开始的待办。
+  pub upcoming: Vec<TodoItem>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayPlanning {
+  /// 本次选中并计划到当天的待办。
+  pub planned: Vec<TodoItem>,
+  /// 前一天计划但未完成、被顺延到当天的待办。
+  pub rolled_over: Vec<TodoItem>,
+}
+
+/// 按本地时区的 `date` 划分未完成待办。
+pub fn build(date: NaiveDate, todos: &[TodoItem], upcoming_days: u32) -> Option<Agenda> {
+  let (start, end) = local_day_bounds(date)?;
+  let (_, horizon) = local_day_bounds(date.checked_add_days(Days::new(upcoming_days.into()))?)?;
+  let mut agenda = Agenda {
+    date: date.format("%Y-%m-%d").to_string(),
+    overdue: Vec::new(),
+    due_today: Vec::new(),
+    planned_today: Vec::new(),
+    upcoming: Vec::new(),
+  };
+
+  for todo in todos.iter().filter(|todo| !todo.completed) {
+    let due = todo.due_time();
+    let planned = todo.planned_time();
+    let group = if due.is_some_and(|at| at < start) {
+      &mut agenda.overdue
+    } else if due.is_some_and(|at| at < end) {
+      &mut agenda.due_today
+    } else if planned.is_some_and(|at| start <= at && at < end) {
+      &mut agenda.planned_today
+    } else if upcoming_time(todo, end, horizon).is_some() {
+      &mut agenda.upcoming
+    } else {
+      continue;
+    };
+    group.push(todo.clone());
+  }
+
+  sort_by_time(&mut agenda.overdue, TodoItem::due_time);
+  sort_by_time(&mut agenda.due_today, TodoItem::due_time);
+  sort_by_time(&mut agenda.planned_today, TodoItem::planned_time);
+  sort_by_time(&mut agenda.upcoming, |todo| {
+    upcoming_time(todo, end, horizon)
+  });
+  Some(agenda)
+}
+
+/// 把 `ids` 中的待办计划到 `date`；`roll_over` 为真时，前一天计划但未完成的待办也顺延到 `date`。
+///
+/// 原计划日期早于 `date` 的待办每被顺延一次，`deferred_count` 加一；已有的计划时刻保持不变。
+pub fn plan(
+  date: NaiveDate,
+  todos: &mut [TodoItem],
+  ids: &[String],
+  roll_over: bool,
+) -> Result<DayPlanning, StorageError> {
+  for id in ids {
+    let todo = todos
+      .iter()
+      .find(|todo| &todo.id == id)
+      .ok_or(StorageError::NotFound("todo"))?;
+    if todo.completed {
+      return Err(StorageError::invalid_field(
+        "todoIds",
+        i18n::t!("agenda.completedTodo", title = todo.title),
+      ));
+    }
+  }
+  let out_of_range = || StorageError::validation(i18n::t!("date.outOfRange"));
+  let (start, end) = local_day_bounds(date).ok_or_else(out_of_range)?;
+  let yesterday = date
+    .pred_opt()
+    .and_then(local_day_bounds)
+    .ok_or_else(out_of_range)?;
+
+  let now = Utc::now().to_rfc3339();
+  let mut result = DayPlanning {
+    planned: Vec::new(),
+    rolled_over: Vec::new(),
+  };
+  for todo in todos.iter_mut() {
+    let previous = todo.planned_time();
+    let chosen = ids.contains(&todo.id);
+    let rolled = !chosen
+      && roll_over
+      && !todo.completed
+      && previous.is_some_and(|at| yesterday.0 <= at && at < yesterday.1);
+    if !chosen && !rolled {
+      continue;
+    }
+    if !previous.is_some_and(|at| start <= at && at < end) {
+      if previous.is_some_and(|at| at < start) {
+        todo.deferred_count += 1;
+      }
+      todo.planned_at = Some(move_plan(todo.planned_at.as_deref(), previous, date));
+      todo.updated_at = now.clone();
+    }
+    if chosen {
+      result.planned.push(todo.clone());
+    } else {
+      result.rolled_over.push(todo.clone());
+    }
+  }
+  Ok(result)
+}
+
+/// 截止或计划时间中落在 `[from, to)` 内的较早者。
+fn upcoming_time(todo: &TodoItem, from: DateTime<Utc>, to: DateTime<Utc>) -> Option<DateTime<Utc>> {
+  [todo.due_time(), todo.planned_time()]
+    .into_iter()
+    .flatten()
+    .filter(|at| from <= *at && *at < to)
+    .min()
+}
+
+fn sort_by_time<F>(todos: &mut [TodoItem], time: F)
+where
+  F: Fn(&TodoItem) -> Option<DateTime<Utc>>,
+{
+  todos.sort_by(|a, b| {
+    time(a)
+      .cmp(&time(b))
+      .then_with(|| a.priority.rank().cmp(&b.priority.rank()))
+      .then_with(|| a.created_at.cmp(&b.created_at))
+  });
+}
+
+/// 计划移到 `date`：只有日期的计划保持只有日期，带时刻的保留原来的本地时刻。
+fn move_plan(raw: Option<&str>, previous: Option<DateTime<Utc>>, date: NaiveDate) -> String {
+  let date_only = raw.is_some_and(|raw| NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d").is_ok());
+  match previous {
+    Some(previous) if !date_only => {
+      let time = previous.with_timezone(&Local).time();
+      date.and_time(time).format("%Y-%m-%dT%H:%M").to_string()
+    }
+    _ => date.format("%Y-%m-%d").to_string(),
+  }
+}
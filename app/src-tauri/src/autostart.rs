@@ -0,0 +1,68 @@
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::{
+  i18n,
+  state::AppState,
+  storage::{StorageError, UserSettings},
+};
+
+/// 登录项启动程序时附带的参数，用来区分开机自启与手动打开。
+pub const AUTOSTART_ARG: &str = "--autostart";
+
+/// 本次启动是否来自系统登录项。
+pub fn launched_at_login() -> bool {
+  std::env::args().any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// 启动时是否应只留在托盘：设置了启动时隐藏，或开机自启且勾选了自启时最小化。
+pub fn start_hidden(settings: &UserSettings) -> bool {
+  settings.start_hidden || (settings.autostart_minimized && launched_at_login())
+}
+
+/// 按设置安装或移除系统登录项（Linux 上为 XDG autostart 的 `.desktop` 文件）。
+pub fn apply(app: &AppHandle, enabled: bool) -> Result<(), StorageError> {
+  let manager = app.autolaunch();
+  let installed = manager.is_enabled().map_err(autostart_error)?;
+  if installed == enabled {
+    return Ok(());
+  }
+  if enabled {
+    manager.enable().map_err(autostart_error)?;
+    log::info!("已添加开机启动项");
+  } else {
+    manager.disable().map_err(autostart_error)?;
+    log::info!("已移除开机启动项");
+  }
+  Ok(())
+}
+
+/// 启动时核对登录项与设置：登录项被用户在系统中删除或手动添加时以系统为准，
+/// 两者都开启时重新写入一次，使登录项指向当前的可执行文件路径。
+pub fn verify(app: &AppHandle) -> Result<(), StorageError> {
+  let state = app.state::<AppState>();
+  let settings = state.store().load_settings()?;
+  let manager = app.autolaunch();
+  let installed = manager.is_enabled().map_err(autostart_error)?;
+
+  if installed && settings.autostart {
+    manager.enable().map_err(autostart_error)?;
+    return Ok(());
+  }
+  if installed != settings.autostart {
+    log::warn!(
+      "开机启动设置（{}）与系统登录项（{}）不一致，已按系统状态更新设置",
+      settings.autostart,
+      installed
+    );
+    state.store().update_settings(|settings| {
+      settings.autostart = installed;
+      Ok(())
+    })?;
+  }
+  Ok(())
+}
+
+fn autostart_error(error: tauri_plugin_autostart::Error) -> StorageError {
+  StorageError::External(i18n::t!("autostart.failed", error = error))
+}
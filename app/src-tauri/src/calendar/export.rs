@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+
+use super::ics::IcsWriter;
+use crate::{
+  i18n,
+  storage::{PomodoroSession, PomodoroSessionKind, TodoItem, TodoPriority},
+};
+
+const PRODUCT_ID: &str = "-//FocusTodo//FocusTodo Calendar//ZH";
+const UID_DOMAIN: &str = "focustodo.local";
+
+pub struct ExportOptions {
+  /// 计划时间块的默认时长，通常取专注时长。
+  pub planned_block_minutes: u32,
+  pub include_sessions: bool,
+}
+
+/// 生成包含待办（VTODO）、计划时间块与专注记录（VEVENT）的日历。
+pub fn build_calendar(
+  todos: &[TodoItem],
+  sessions: &[PomodoroSession],
+  options: &ExportOptions,
+) -> String {
+  let stamp = Utc::now();
+  let mut ics = IcsWriter::new();
+  ics
+    .begin("VCALENDAR")
+    .raw("VERSION", "2.0")
+    .text("PRODID", PRODUCT_ID)
+    .raw("CALSCALE", "GREGORIAN")
+    .raw("METHOD", "PUBLISH")
+    .text("X-WR-CALNAME", "FocusTodo");
+
+  for todo in todos {
+    if let Some(due) = todo.due_time() {
+      ics
+        .begin("VTODO")
+        .text("UID", &format!("todo-{}@{UID_DOMAIN}", todo.id))
+        .utc("DTSTAMP", stamp)
+        .text("SUMMARY", &todo.title)
+        .utc("DUE", due);
+      if let Some(planned) = todo.planned_time().filter(|planned| *planned <= due) {
+        ics.utc("DTSTART", planned);
+      }
+      write_todo_common(&mut ics, todo);
+      if todo.completed {
+        ics
+          .raw("STATUS", "COMPLETED")
+          .raw("PERCENT-COMPLETE", "100");
+        if let Some(done) = todo
+          .completed_at
+          .as_deref()
+          .and_then(crate::storage::parse_timestamp)
+        {
+          ics.utc("COMPLETED", done);
+        }
+      } else {
+        ics.raw("STATUS", "NEEDS-ACTION");
+      }
+      ics.end("VTODO");
+    }
+
+    if let Some(planned) = todo.planned_time() {
+      let end = planned + Duration::minutes(i64::from(options.planned_block_minutes.max(1)));
+      ics
+        .begin("VEVENT")
+        .text("UID", &format!("plan-{}@{UID_DOMAIN}", todo.id))
+        .utc("DTSTAMP", stamp)
+        .text("SUMMARY", &todo.title)
+        .utc("DTSTART", planned)
+        .utc("DTEND", end)
+        .raw("TRANSP", "OPAQUE");
+      write_todo_common(&mut ics, todo);
+      ics.end("VEVENT");
+    }
+  }
+
+  if options.include_sessions {
+    let titles: HashMap<&str, &str> = todos
+      .iter()
+      .map(|todo| (todo.id.as_str(), todo.title.as_str()))
+      .collect();
+    for session in sessions
+      .iter()
+      .filter(|session| session.completed && session.r#type == PomodoroSessionKind::Focus)
+    {
+      let (Some(start), Some(end)) = (session.start_time(), session.end_time()) else {
+        continue;
+      };
+      let title = session
+        .todo_id
+        .as_deref()
+        .and_then(|id| titles.get(id))
+        .map(|title| {
+          i18n::t!(
+            "calendar.sessionSummary",
+            phase = i18n::t!("phase.focus"),
+            title = title
+          )
+        })
+        .unwrap_or_else(|| i18n::t!("phase.focus"));
+      ics
+        .begin("VEVENT")
+        .text("UID", &format!("session-{}@{UID_DOMAIN}", session.id))
+        .utc("DTSTAMP", stamp)
+        .text("SUMMARY", &title)
+        .utc("DTSTART", start)
+        .utc("DTEND", end.max(start))
+        .text("CATEGORIES", "FocusTodo")
+        .raw("TRANSP", "OPAQUE")
+        .end("VEVENT");
+    }
+  }
+
+  ics.end("VCALENDAR");
+  ics.finish()
+}
+
+fn write_todo_common(ics: &mut IcsWriter, todo: &TodoItem) {
+  if let Some(detail) = todo
+    .detail
+    .as_deref()
+    .filter(|detail| !detail.trim().is_empty())
+  {
+    ics.text("DESCRIPTION", detail);
+  }
+  ics.raw("PRIORITY", ics_priority(&todo.priority));
+  if !todo.tags.is_empty() {
+    let categories = todo
+      .tags
+      .iter()
+      .map(|tag| super::ics::escape_text(tag))
+      .collect::<Vec<_>>()
+      .join(",");
+    ics.raw("CATEGORIES", &categories);
+  }
+}
+
+/// RFC 5545 优先级：1 最高，5 中等，9 最低。
+fn ics_priority(priority: &TodoPriority) -> &'static str {
+  match priority {
+    TodoPriority::High => "1",
+    TodoPriority::Medium => "5",
+    TodoPriority::Low => "9",
+  }
+}
@@ -0,0 +1,81 @@
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  thread,
+  time::{Duration, Instant},
+};
+
+use tauri::{AppHandle, Manager};
+
+use crate::{
+  i18n,
+  state::AppState,
+  storage::{FileOperation, IcsFeedSetting, StorageError},
+};
+
+pub const FEED_FILE_NAME: &str = "focustodo.ics";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// 后台定期重写订阅文件，供日历应用以本地文件方式订阅。
+pub fn spawn(app: AppHandle) {
+  thread::spawn(move || {
+    let mut last_written: Option<Instant> = None;
+    loop {
+      thread::sleep(POLL_INTERVAL);
+      let state = app.state::<AppState>();
+      let Ok(settings) = state.store().load_settings() else {
+        continue;
+      };
+      let feed = settings.ics_feed;
+      if !feed.enabled {
+        last_written = None;
+        continue;
+      }
+      let interval = Duration::from_secs(u64::from(feed.interval_minutes.max(1)) * 60);
+      if last_written.is_some_and(|at| at.elapsed() < interval) {
+        continue;
+      }
+      match write_feed(&state, &feed) {
+        Ok(_) => last_written = Some(Instant::now()),
+        Err(err) => log::warn!("更新日历订阅文件失败: {}", err),
+      }
+    }
+  });
+}
+
+/// 立即生成订阅文件，返回写入路径。
+pub fn write_feed(state: &AppState, feed: &IcsFeedSetting) -> Result<PathBuf, StorageError> {
+  let folder = feed
+    .folder
+    .as_deref()
+    .filter(|folder| !folder.trim().is_empty())
+    .ok_or_else(|| StorageError::validation(i18n::t!("calendar.feedFolderRequired")))?;
+  let folder = Path::new(folder);
+  if !folder.is_dir() {
+    return Err(StorageError::validation(i18n::t!(
+      "calendar.feedFolderMissing",
+      path = folder.display()
+    )));
+  }
+  let content = super::render(state, feed.include_sessions)?;
+  let path = folder.join(FEED_FILE_NAME);
+  write_atomic(&path, content.as_bytes())?;
+  Ok(path)
+}
+
+/// 先写入同目录下的临时文件再替换，临时文件名带上原文件名与进程号，避免覆盖用户目录里的其他文件。
+pub fn write_atomic(path: &Path, payload: &[u8]) -> Result<(), StorageError> {
+  let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+  temp_name.push(format!(".{}.tmp", std::process::id()));
+  let temp_path = path.with_file_name(temp_name);
+  let result = fs::write(&temp_path, payload)
+    .map_err(StorageError::file(FileOperation::Write, &temp_path))
+    .and_then(|_| {
+      fs::rename(&temp_path, path).map_err(StorageError::file(FileOperation::Replace, path))
+    });
+  if result.is_err() {
+    let _ = fs::remove_file(&temp_path);
+  }
+  result
+}
@@ -0,0 +1,209 @@
+use chrono::{DateTime, Utc};
+
+const MAX_LINE_OCTETS: usize = 75;
+
+/// 按 RFC 5545 生成 iCalendar 文本：CRLF 换行、75 字节折行、文本转义。
+#[derive(Default)]
+pub struct IcsWriter {
+  buf: String,
+}
+
+impl IcsWriter {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn begin(&mut self, component: &str) -> &mut Self {
+    self.line(&format!("BEGIN:{component}"))
+  }
+
+  pub fn end(&mut self, component: &str) -> &mut Self {
+    self.line(&format!("END:{component}"))
+  }
+
+  /// 写入文本属性，值会被转义。
+  pub fn text(&mut self, name: &str, value: &str) -> &mut Self {
+    self.line(&format!("{name}:{}", escape_text(value)))
+  }
+
+  /// 写入无需转义的属性（时间、枚举值等），`name` 可带参数。
+  pub fn raw(&mut self, name: &str, value: &str) -> &mut Self {
+    self.line(&format!("{name}:{value}"))
+  }
+
+  pub fn utc(&mut self, name: &str, value: DateTime<Utc>) -> &mut Self {
+    self.raw(name, &format_utc(value))
+  }
+
+  pub fn finish(self) -> String {
+    self.buf
+  }
+
+  fn line(&mut self, content: &str) -> &mut Self {
+    let mut octets = 0;
+    for ch in content.chars() {
+      let width = ch.len_utf8();
+      if octets + width > MAX_LINE_OCTETS {
+        self.buf.push_str("\r\n ");
+        octets = 1;
+      }
+      self.buf.push(ch);
+      octets += width;
+    }
+    self.buf.push_str("\r\n");
+    self
+  }
+}
+
+pub fn escape_text(value: &str) -> String {
+  let mut escaped = String::with_capacity(value.len());
+  for ch in value.chars() {
+    match ch {
+      '\\' => escaped.push_str("\\\\"),
+      ';' => escaped.push_str("\\;"),
+      ',' => escaped.push_str("\\,"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => {}
+      other => escaped.push(other),
+    }
+  }
+  escaped
+}
+
+pub fn format_utc(value: DateTime<Utc>) -> String {
+  value.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct IcsProperty {
+  pub name: String,
+  pub params: Vec<(String, String)>,
+  pub value: String,
+}
+
+impl IcsProperty {
+  pub fn param(&self, name: &str) -> Option<&str> {
+    self
+      .params
+      .iter()
+      .find(|(key, _)| key.eq_ignore_ascii_case(name))
+      .map(|(_, value)| value.as_str())
+  }
+}
+
+/// 一个 VEVENT 组件的属性集合（不含嵌套的 VALARM 等子组件）。
+#[derive(Debug, Clone, Default)]
+pub struct IcsComponent {
+  pub properties: Vec<IcsProperty>,
+}
+
+impl IcsComponent {
+  pub fn get(&self, name: &str) -> Option<&IcsProperty> {
+    self.properties.iter().find(|prop| prop.name == name)
+  }
+
+  pub fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a IcsProperty> + 'a {
+    self.properties.iter().filter(move |prop| prop.name == name)
+  }
+
+  pub fn text(&self, name: &str) -> Option<String> {
+    self.get(name).map(|prop| unescape_text(&prop.value))
+  }
+}
+
+/// 解析日历文本中的所有 VEVENT。
+pub fn parse_events(content: &str) -> Vec<IcsComponent> {
+  let mut events = Vec::new();
+  let mut current: Option<IcsComponent> = None;
+  let mut nested_depth = 0usize;
+
+  for line in unfold_lines(content) {
+    let Some(prop) = parse_line(&line) else {
+      continue;
+    };
+    match (prop.name.as_str(), prop.value.to_ascii_uppercase().as_str()) {
+      ("BEGIN", "VEVENT") if current.is_none() => current = Some(IcsComponent::default()),
+      ("END", "VEVENT") if nested_depth == 0 => {
+        if let Some(event) = current.take() {
+          events.push(event);
+        }
+      }
+      ("BEGIN", _) if current.is_some() => nested_depth += 1,
+      ("END", _) if current.is_some() => nested_depth = nested_depth.saturating_sub(1),
+      _ => {
+        if let (Some(event), 0) = (current.as_mut(), nested_depth) {
+          event.properties.push(prop);
+        }
+      }
+    }
+  }
+  events
+}
+
+fn unfold_lines(content: &str) -> Vec<String> {
+  let mut lines: Vec<String> = Vec::new();
+  for raw in content.split('\n') {
+    let raw = raw.strip_suffix('\r').unwrap_or(raw);
+    if let Some(continuation) = raw.strip_prefix([' ', '\t']) {
+      if let Some(last) = lines.last_mut() {
+        last.push_str(continuation);
+        continue;
+      }
+    }
+    if !raw.is_empty() {
+      lines.push(raw.to_string());
+    }
+  }
+  lines
+}
+
+fn parse_line(line: &str) -> Option<IcsProperty> {
+  // 参数值可以用引号包裹并包含冒号，需要跳过引号内的分隔符。
+  let mut in_quotes = false;
+  let mut split_at = None;
+  for (index, ch) in line.char_indices() {
+    match ch {
+      '"' => in_quotes = !in_quotes,
+      ':' if !in_quotes => {
+        split_at = Some(index);
+        break;
+      }
+      _ => {}
+    }
+  }
+  let split_at = split_at?;
+  let (head, value) = (&line[..split_at], &line[split_at + 1..]);
+  let mut parts = head.split(';');
+  let name = parts.next()?.trim().to_ascii_uppercase();
+  let params = parts
+    .filter_map(|param| param.split_once('='))
+    .map(|(key, value)| {
+      (
+        key.trim().to_ascii_uppercase(),
+        value.trim_matches('"').to_string(),
+      )
+    })
+    .collect();
+  Some(IcsProperty {
+    name,
+    params,
+    value: value.to_string(),
+  })
+}
+
+pub fn unescape_text(value: &str) -> String {
+  let mut result = String::with_capacity(value.len());
+  let mut chars = value.chars();
+  while let Some(ch) = chars.next() {
+    if ch != '\\' {
+      result.push(ch);
+      continue;
+    }
+    match chars.next() {
+      Some('n') | Some('N') => result.push('\n'),
+      Some(other) => result.push(other),
+      None => result.push('\\'),
+    }
+  }
+  result
+}
@@ -0,0 +1,795 @@
+use std::{collections::HashSet, fs, path::Path, thread, time::Duration as StdDuration};
+
+use chrono::{
+  DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+use tauri::{AppHandle, Manager};
+use uuid::Uuid;
+
+use super::ics::{parse_events, IcsComponent, IcsProperty};
+use crate::{
+  i18n,
+  state::AppState,
+  storage::{CalendarEvent, CalendarSource, FileOperation, StorageError},
+};
+
+const WATCH_INTERVAL: StdDuration = StdDuration::from_secs(60);
+/// 重复日程只展开到这个时间窗口内，避免无限序列撑爆数据文件；跨日后由监听线程重新展开。
+const EXPAND_PAST_DAYS: i64 = 60;
+const EXPAND_FUTURE_DAYS: i64 = 365;
+const MAX_OCCURRENCES: usize = 2000;
+const MAX_STEPS: i64 = 100_000;
+
+/// 导入（或重新导入）一个 `.ics` 文件，同一路径的旧日程会被整体替换。
+pub fn import_file(
+  state: &AppState,
+  path: &str,
+  watch: Option<bool>,
+) -> Result<CalendarSource, StorageError> {
+  let file = Path::new(path);
+  if !file.is_file() {
+    return Err(StorageError::validation(i18n::t!(
+      "calendar.fileMissing",
+      path = path
+    )));
+  }
+  let content = fs::read_to_string(file).map_err(StorageError::file(FileOperation::Read, file))?;
+  let modified_at = modified_time(file);
+
+  let mut calendar = state.store().load_calendar()?;
+  let existing = calendar
+    .sources
+    .iter()
+    .position(|source| source.path == path);
+  let source_id = existing
+    .map(|index| calendar.sources[index].id.clone())
+    .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+  let now = Utc::now();
+  let window = (
+    now - Duration::days(EXPAND_PAST_DAYS),
+    now + Duration::days(EXPAND_FUTURE_DAYS),
+  );
+  let events = expand_events(&parse_events(&content), &source_id, window);
+
+  let source = CalendarSource {
+    id: source_id.clone(),
+    path: path.to_string(),
+    watch: watch
+      .or_else(|| existing.map(|index| calendar.sources[index].watch))
+      .unwrap_or(false),
+    modified_at,
+    imported_at: now.to_rfc3339(),
+    event_count: events.len() as u32,
+  };
+  match existing {
+    Some(index) => calendar.sources[index] = source.clone(),
+    None => calendar.sources.push(source.clone()),
+  }
+  calendar.events.retain(|event| event.source_id != source_id);
+  calendar.events.extend(events);
+  state.store().save_calendar(&calendar)?;
+  Ok(source)
+}
+
+/// 定期检查日历文件：被监听的文件修改时间变化时自动重新导入；
+/// 跨日后按未改动的源文件重新展开重复日程，让展开窗口随日期前移。
+pub fn spawn_watcher(app: AppHandle) {
+  thread::spawn(move || loop {
+    thread::sleep(WATCH_INTERVAL);
+    let state = app.state::<AppState>();
+    let Ok(calendar) = state.store().load_calendar() else {
+      continue;
+    };
+    let today = Local::now().date_naive();
+    for source in &calendar.sources {
+      let modified = modified_time(Path::new(&source.path));
+      if modified.is_none() {
+        continue;
+      }
+      let changed = modified != source.modified_at;
+      // 未监听的文件改动后不自动读取，保留导入时的内容。
+      let reimport = if changed {
+        source.watch
+      } else {
+        imported_before(&source.imported_at, today)
+      };
+      if !reimport {
+        continue;
+      }
+      if let Err(err) = import_file(&state, &source.path, Some(source.watch)) {
+        log::warn!("重新导入日历文件失败 {}: {}", source.path, err);
+      }
+    }
+  });
+}
+
+/// 导入时间是否早于 `today`（本地日期）；无法解析时按需要重新展开处理。
+fn imported_before(imported_at: &str, today: NaiveDate) -> bool {
+  DateTime::parse_from_rfc3339(imported_at)
+    .map(|at| at.with_timezone(&Local).date_naive() < today)
+    .unwrap_or(true)
+}
+
+fn modified_time(path: &Path) -> Option<String> {
+  let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+  Some(DateTime::<Utc>::from(modified).to_rfc3339())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+  Utc,
+  Named(Tz),
+  Local,
+}
+
+impl Zone {
+  fn resolve(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match self {
+      Zone::Utc => Some(Utc.from_utc_datetime(&naive)),
+      Zone::Named(tz) => tz
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc)),
+      Zone::Local => Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc)),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IcsTime {
+  naive: NaiveDateTime,
+  zone: Zone,
+  all_day: bool,
+}
+
+impl IcsTime {
+  fn resolve(&self) -> Option<DateTime<Utc>> {
+    self.zone.resolve(self.naive)
+  }
+}
+
+fn parse_time(prop: &IcsProperty) -> Option<IcsTime> {
+  parse_time_value(prop, prop.value.trim())
+}
+
+fn parse_time_value(prop: &IcsProperty, value: &str) -> Option<IcsTime> {
+  let is_date = prop
+    .param("VALUE")
+    .is_some_and(|kind| kind.eq_ignore_ascii_case("DATE"))
+    || value.len() == 8;
+  if is_date {
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    return Some(IcsTime {
+      naive: date.and_hms_opt(0, 0, 0)?,
+      zone: Zone::Local,
+      all_day: true,
+    });
+  }
+  if let Some(utc) = value.strip_suffix('Z') {
+    return Some(IcsTime {
+      naive: NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?,
+      zone: Zone::Utc,
+      all_day: false,
+    });
+  }
+  let zone = prop
+    .param("TZID")
+    .and_then(|tzid| tzid.trim_start_matches('/').parse::<Tz>().ok())
+    .map(Zone::Named)
+    .unwrap_or(Zone::Local);
+  Some(IcsTime {
+    naive: NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?,
+    zone,
+    all_day: false,
+  })
+}
+
+/// 解析 RFC 5545 的 DURATION，例如 `PT1H30M`、`P1D`、`-PT15M`。
+fn parse_duration(value: &str) -> Option<Duration> {
+  let value = value.trim();
+  let (negative, rest) = match value.strip_prefix('-') {
+    Some(rest) => (true, rest),
+    None => (false, value.strip_prefix('+').unwrap_or(value)),
+  };
+  let rest = rest.strip_prefix('P')?;
+  let mut total = Duration::zero();
+  let mut number = String::new();
+  let mut in_time = false;
+  for ch in rest.chars() {
+    match ch {
+      'T' => in_time = true,
+      '0'..='9' => number.push(ch),
+      unit => {
+        let amount: i64 = number.parse().ok()?;
+        number.clear();
+        total += match (unit, in_time) {
+          ('W', false) => Duration::weeks(amount),
+          ('D', false) => Duration::days(amount),
+          ('H', true) => Duration::hours(amount),
+          ('M', true) => Duration::minutes(amount),
+          ('S', true) => Duration::seconds(amount),
+          _ => return None,
+        };
+      }
+    }
+  }
+  Some(if negative { -total } else { total })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+  Daily,
+  Weekly,
+  Monthly,
+  Yearly,
+}
+
+#[derive(Debug, Clone)]
+struct RecurrenceRule {
+  frequency: Frequency,
+  interval: u32,
+  count: Option<usize>,
+  until: Option<DateTime<Utc>>,
+  /// `(序数, 星期)`，序数为 0 表示每个该星期几，负数从月末倒数。
+  by_day: Vec<(i8, Weekday)>,
+  /// 月内第几天，负数从月末倒数。
+  by_month_day: Vec<i8>,
+}
+
+/// 解析 RRULE；不支持的部分（如 BYSETPOS）返回 `Err`，由调用方跳过该日程，避免生成错误的日期。
+fn parse_rrule(prop: &IcsProperty) -> Result<RecurrenceRule, String> {
+  let mut rule = RecurrenceRule {
+    frequency: Frequency::Daily,
+    interval: 1,
+    count: None,
+    until: None,
+    by_day: Vec::new(),
+    by_month_day: Vec::new(),
+  };
+  let mut has_frequency = false;
+  for part in prop.value.split(';') {
+    let Some((key, value)) = part.split_once('=') else {
+      continue;
+    };
+    match key.to_ascii_uppercase().as_str() {
+      "FREQ" => {
+        has_frequency = true;
+        rule.frequency = match value.to_ascii_uppercase().as_str() {
+          "DAILY" => Frequency::Daily,
+          "WEEKLY" => Frequency::Weekly,
+          "MONTHLY" => Frequency::Monthly,
+          "YEARLY" => Frequency::Yearly,
+          // 更细粒度的重复（按小时、分钟）不适合作为忙碌时间导入。
+          _ => return Err(part.to_string()),
+        }
+      }
+      "INTERVAL" => rule.interval = value.parse().ok().filter(|n| *n > 0).unwrap_or(1),
+      "COUNT" => rule.count = value.parse().ok(),
+      "UNTIL" => rule.until = parse_time_value(prop, value).and_then(|time| time.resolve()),
+      "BYDAY" => {
+        rule.by_day = value
+          .split(',')
+          .map(parse_by_day)
+          .collect::<Option<_>>()
+          .ok_or_else(|| part.to_string())?;
+      }
+      "BYMONTHDAY" => {
+        rule.by_month_day = value
+          .split(',')
+          .map(|day| {
+            day
+              .trim()
+              .parse::<i8>()
+              .ok()
+              .filter(|day| (1..=31).contains(&day.abs()))
+          })
+          .collect::<Option<_>>()
+          .ok_or_else(|| part.to_string())?;
+      }
+      // 周起始日只影响间隔大于 1 的按周重复，这里统一按周一计算。
+      "WKST" => {}
+      _ => return Err(part.to_string()),
+    }
+  }
+  if !has_frequency {
+    return Err(prop.value.clone());
+  }
+  let has_ordinal = rule.by_day.iter().any(|(ordinal, _)| *ordinal != 0);
+  let supported = match rule.frequency {
+    Frequency::Daily | Frequency::Weekly => !has_ordinal && rule.by_month_day.is_empty(),
+    Frequency::Monthly => true,
+    Frequency::Yearly => rule.by_day.is_empty() && rule.by_month_day.is_empty(),
+  };
+  if !supported {
+    return Err(prop.value.clone());
+  }
+  Ok(rule)
+}
+
+/// 解析 BYDAY 中的一项，例如 `MO`、`2MO`、`-1FR`。
+fn parse_by_day(value: &str) -> Option<(i8, Weekday)> {
+  let value = value.trim();
+  let split = value.len().checked_sub(2)?;
+  let (ordinal, day) = (value.get(..split)?, value.get(split..)?);
+  let ordinal = match ordinal.trim_start_matches('+') {
+    "" => 0,
+    ordinal => ordinal
+      .parse::<i8>()
+      .ok()
+      .filter(|n| *n != 0 && (1..=53).contains(&n.abs()))?,
+  };
+  Some((ordinal, parse_weekday(day)?))
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+  Some(match value.to_ascii_uppercase().as_str() {
+    "MO" => Weekday::Mon,
+    "TU" => Weekday::Tue,
+    "WE" => Weekday::Wed,
+    "TH" => Weekday::Thu,
+    "FR" => Weekday::Fri,
+    "SA" => Weekday::Sat,
+    "SU" => Weekday::Sun,
+    _ => return None,
+  })
+}
+
+/// 按规则生成 `[from, horizon]` 内的本地（事件时区）开始时刻；COUNT 仍从首次发生起计数。
+fn occurrences(
+  start: NaiveDateTime,
+  rule: &RecurrenceRule,
+  from: NaiveDateTime,
+  horizon: NaiveDateTime,
+) -> Vec<NaiveDateTime> {
+  let mut result = Vec::new();
+  let interval = i64::from(rule.interval);
+  let mut generated = 0usize;
+  let mut step = 0i64;
+
+  'outer: while result.len() < MAX_OCCURRENCES && step <= MAX_STEPS {
+    let candidates: Vec<NaiveDateTime> = match rule.frequency {
+      Frequency::Daily => Some(start + Duration::days(step * interval))
+        .filter(|candidate| {
+          rule.by_day.is_empty()
+            || rule
+              .by_day
+              .iter()
+              .any(|(_, day)| *day == candidate.weekday())
+        })
+        .into_iter()
+        .collect(),
+      Frequency::Weekly => {
+        let week_start = start.date()
+          - Duration::days(i64::from(start.weekday().num_days_from_monday()))
+          + Duration::weeks(step * interval);
+        let mut days = if rule.by_day.is_empty() {
+          vec![start.weekday()]
+        } else {
+          rule.by_day.iter().map(|(_, day)| *day).collect()
+        };
+        days.sort_by_key(|day| day.num_days_from_monday());
+        days
+          .into_iter()
+          .map(|day| {
+            (week_start + Duration::days(i64::from(day.num_days_from_monday())))
+              .and_time(start.time())
+          })
+          .filter(|candidate| *candidate >= start)
+          .collect()
+      }
+      Frequency::Monthly => start
+        .date()
+        .with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new((step * interval) as u32)))
+        .map(|month| {
+          month_days(month, start.day(), rule)
+            .into_iter()
+            .map(|date| date.and_time(start.time()))
+            .filter(|candidate| *candidate >= start)
+            .collect()
+        })
+        .unwrap_or_default(),
+      Frequency::Yearly => start
+        .date()
+        .with_year(start.year() + (step * interval) as i32)
+        .map(|date| vec![date.and_time(start.time())])
+        .unwrap_or_default(),
+    };
+
+    for candidate in candidates {
+      if candidate > horizon || rule.count.is_some_and(|count| generated >= count) {
+        break 'outer;
+      }
+      generated += 1;
+      if candidate >= from {
+        result.push(candidate);
+      }
+    }
+    step += 1;
+  }
+  result
+}
+
+/// 按 BYDAY/BYMONTHDAY 取 `month` 所在月份中的日期，两者都给出时取交集，都没有时沿用开始日期的日。
+fn month_days(month: NaiveDate, start_day: u32, rule: &RecurrenceRule) -> Vec<NaiveDate> {
+  let length = month
+    .checked_add_months(Months::new(1))
+    .map_or(31, |next| (next - month).num_days() as i32);
+  let resolve = |day: i32| {
+    let day = if day < 0 { length + 1 + day } else { day };
+    (1..=length)
+      .contains(&day)
+      .then(|| month.with_day(day as u32))
+      .flatten()
+  };
+
+  let month_days: Vec<NaiveDate> = if rule.by_month_day.is_empty() {
+    resolve(start_day as i32).into_iter().collect()
+  } else {
+    rule
+      .by_month_day
+      .iter()
+      .filter_map(|day| resolve(i32::from(*day)))
+      .collect()
+  };
+  let mut days: Vec<NaiveDate> = if rule.by_day.is_empty() {
+    month_days
+  } else {
+    let weekdays = rule.by_day.iter().flat_map(|(ordinal, weekday)| {
+      let matching: Vec<NaiveDate> = (1..=length)
+        .filter_map(resolve)
+        .filter(|date| date.weekday() == *weekday)
+        .collect();
+      match *ordinal {
+        0 => matching,
+        n if n > 0 => matching.get(n as usize - 1).copied().into_iter().collect(),
+        n => matching
+          .len()
+          .checked_sub(n.unsigned_abs() as usize)
+          .and_then(|index| matching.get(index).copied())
+          .into_iter()
+          .collect(),
+      }
+    });
+    weekdays
+      .filter(|date| rule.by_month_day.is_empty() || month_days.contains(date))
+      .collect()
+  };
+  days.sort();
+  days.dedup();
+  days
+}
+
+fn expand_events(
+  components: &[IcsComponent],
+  source_id: &str,
+  window: (DateTime<Utc>, DateTime<Utc>),
+) -> Vec<CalendarEvent> {
+  // 被 RECURRENCE-ID 单独修改过的实例需要从主序列中剔除。
+  let overridden: HashSet<(String, DateTime<Utc>)> = components
+    .iter()
+    .filter_map(|component| {
+      let uid = component.text("UID")?;
+      let recurrence = parse_time(component.get("RECURRENCE-ID")?)?.resolve()?;
+      Some((uid, recurrence))
+    })
+    .collect();
+
+  let mut events = Vec::new();
+  for (index, component) in components.iter().enumerate() {
+    let status = component
+      .get("STATUS")
+      .map(|prop| prop.value.to_ascii_uppercase());
+    if status.as_deref() == Some("CANCELLED") {
+      continue;
+    }
+    let Some(start) = component.get("DTSTART").and_then(parse_time) else {
+      continue;
+    };
+    let length = component
+      .get("DTEND")
+      .and_then(parse_time)
+      .and_then(|end| Some(end.resolve()? - start.resolve()?))
+      .or_else(|| {
+        component
+          .get("DURATION")
+          .and_then(|prop| parse_duration(&prop.value))
+      })
+      .unwrap_or_else(|| {
+        if start.all_day {
+          Duration::days(1)
+        } else {
+          Duration::zero()
+        }
+      });
+
+    let uid = component.text("UID");
+    let title = component
+      .text("SUMMARY")
+      .unwrap_or_else(|| i18n::t!("calendar.untitled"));
+    let location = component
+      .text("LOCATION")
+      .filter(|location| !location.is_empty());
+    let busy = component
+      .get("TRANSP")
+      .is_none_or(|prop| !prop.value.eq_ignore_ascii_case("TRANSPARENT"));
+
+    let excluded: HashSet<DateTime<Utc>> = component
+      .all("EXDATE")
+      .flat_map(|prop| {
+        prop
+          .value
+          .split(',')
+          .filter_map(|value| parse_time_value(prop, value.trim())?.resolve())
+          .collect::<Vec<_>>()
+      })
+      .collect();
+
+    let is_override = component.get("RECURRENCE-ID").is_some();
+    // 本地时间与 UTC 最多相差一天，窗口两端各放宽一天后再精确过滤。
+    let from = window.0.naive_utc() - length - Duration::days(1);
+    let horizon = window.1.naive_utc() + Duration::days(1);
+    let rule = match component.get("RRULE").filter(|_| !is_override) {
+      Some(prop) => match parse_rrule(prop) {
+        Ok(rule) => Some(rule),
+        Err(unsupported) => {
+          log::warn!(
+            "跳过不支持的重复规则 {}（{}）",
+            unsupported,
+            uid.as_deref().unwrap_or(&title)
+          );
+          continue;
+        }
+      },
+      None => None,
+    };
+    let starts = match rule {
+      Some(rule) => occurrences(start.naive, &rule, from, horizon)
+        .into_iter()
+        .filter_map(|naive| start.zone.resolve(naive))
+        .filter(|at| rule.until.is_none_or(|until| *at <= until))
+        .collect(),
+      None => start.resolve().into_iter().collect::<Vec<_>>(),
+    };
+
+    for begin in starts {
+      let end = begin + length;
+      if end < window.0 || begin > window.1 || excluded.contains(&begin) {
+        continue;
+      }
+      if !is_override {
+        if let Some(uid) = &uid {
+          if overridden.contains(&(uid.clone(), begin)) {
+            continue;
+          }
+        }
+      }
+      let key = uid.clone().unwrap_or_else(|| format!("event-{index}"));
+      events.push(CalendarEvent {
+        id: format!("{source_id}:{key}:{}", begin.timestamp()),
+        source_id: source_id.to_string(),
+        uid: uid.clone(),
+        title: title.clone(),
+        location: location.clone(),
+        start_at: format_boundary(begin, start.all_day),
+        end_at: format_boundary(end, start.all_day),
+        all_day: start.all_day,
+        busy,
+      });
+    }
+  }
+  events.sort_by(|a, b| a.start_at.cmp(&b.start_at));
+  events
+}
+
+/// 全天日程只保存日期，按本地时区理解；其他日程保存 RFC 3339。
+fn format_boundary(value: DateTime<Utc>, all_day: bool) -> String {
+  if all_day {
+    value.with_timezone(&Local).format("%Y-%m-%d").to_string()
+  } else {
+    value.to_rfc3339()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rrule(value: &str) -> IcsProperty {
+    IcsProperty {
+      name: "RRULE".to_string(),
+      params: Vec::new(),
+      value: value.to_string(),
+    }
+  }
+
+  fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+  }
+
+  fn at(year: i32, month: u32, day: u32, hour: u32) -> NaiveDateTime {
+    date(year, month, day).and_hms_opt(hour, 0, 0).unwrap()
+  }
+
+  fn expand(value: &str, start: NaiveDateTime) -> Vec<NaiveDateTime> {
+    let rule = parse_rrule(&rrule(value)).unwrap();
+    occurrences(start, &rule, start, at(2027, 12, 31, 0))
+  }
+
+  #[test]
+  fn parses_durations() {
+    assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+    assert_eq!(parse_duration("P1D"), Some(Duration::days(1)));
+    assert_eq!(parse_duration("P1W"), Some(Duration::weeks(1)));
+    assert_eq!(
+      parse_duration("P1DT2H"),
+      Some(Duration::days(1) + Duration::hours(2))
+    );
+    assert_eq!(parse_duration("-PT15M"), Some(Duration::minutes(-15)));
+    assert_eq!(parse_duration("+PT45S"), Some(Duration::seconds(45)));
+    assert_eq!(parse_duration("PT1D"), None);
+    assert_eq!(parse_duration("1H"), None);
+    assert_eq!(parse_duration("PTH"), None);
+  }
+
+  #[test]
+  fn parses_rule_parts() {
+    let rule = parse_rrule(&rrule(
+      "FREQ=MONTHLY;INTERVAL=2;COUNT=5;BYDAY=2MO,-1FR,+1SU;WKST=SU",
+    ))
+    .unwrap();
+    assert_eq!(rule.frequency, Frequency::Monthly);
+    assert_eq!(rule.interval, 2);
+    assert_eq!(rule.count, Some(5));
+    assert_eq!(
+      rule.by_day,
+      vec![(2, Weekday::Mon), (-1, Weekday::Fri), (1, Weekday::Sun)]
+    );
+
+    let rule = parse_rrule(&rrule("FREQ=MONTHLY;BYMONTHDAY=1,-1")).unwrap();
+    assert_eq!(rule.by_month_day, vec![1, -1]);
+
+    let rule = parse_rrule(&rrule("FREQ=DAILY;UNTIL=20260131T000000Z")).unwrap();
+    assert_eq!(rule.until, Some(Utc.from_utc_datetime(&at(2026, 1, 31, 0))));
+  }
+
+  #[test]
+  fn rejects_unsupported_rules() {
+    for value in [
+      "INTERVAL=2",
+      "FREQ=HOURLY",
+      "FREQ=MONTHLY;BYDAY=1MO;BYSETPOS=1",
+      "FREQ=WEEKLY;BYDAY=1MO",
+      "FREQ=DAILY;BYMONTHDAY=1",
+      "FREQ=YEARLY;BYMONTHDAY=1",
+      "FREQ=MONTHLY;BYMONTHDAY=32",
+      "FREQ=MONTHLY;BYDAY=0MO",
+      "FREQ=MONTHLY;BYDAY=XX",
+    ] {
+      assert!(parse_rrule(&rrule(value)).is_err(), "{value}");
+    }
+  }
+
+  #[test]
+  fn month_days_skip_missing_dates() {
+    let rule = parse_rrule(&rrule("FREQ=MONTHLY")).unwrap();
+    assert!(month_days(date(2026, 2, 1), 31, &rule).is_empty());
+    assert_eq!(
+      month_days(date(2026, 3, 1), 31, &rule),
+      vec![date(2026, 3, 31)]
+    );
+
+    let rule = parse_rrule(&rrule("FREQ=MONTHLY;BYMONTHDAY=-1")).unwrap();
+    assert_eq!(
+      month_days(date(2026, 2, 1), 1, &rule),
+      vec![date(2026, 2, 28)]
+    );
+  }
+
+  #[test]
+  fn month_days_resolve_weekday_ordinals() {
+    let fifth_friday = parse_rrule(&rrule("FREQ=MONTHLY;BYDAY=5FR")).unwrap();
+    assert_eq!(
+      month_days(date(2026, 1, 1), 1, &fifth_friday),
+      vec![date(2026, 1, 30)]
+    );
+    assert!(month_days(date(2026, 2, 1), 1, &fifth_friday).is_empty());
+
+    let last_friday = parse_rrule(&rrule("FREQ=MONTHLY;BYDAY=-1FR")).unwrap();
+    assert_eq!(
+      month_days(date(2026, 2, 1), 1, &last_friday),
+      vec![date(2026, 2, 27)]
+    );
+
+    let friday_13th = parse_rrule(&rrule("FREQ=MONTHLY;BYDAY=FR;BYMONTHDAY=13")).unwrap();
+    assert_eq!(
+      month_days(date(2026, 2, 1), 1, &friday_13th),
+      vec![date(2026, 2, 13)]
+    );
+    assert!(month_days(date(2026, 4, 1), 1, &friday_13th).is_empty());
+  }
+
+  #[test]
+  fn monthly_on_the_31st_skips_short_months() {
+    assert_eq!(
+      expand("FREQ=MONTHLY;COUNT=4", at(2026, 1, 31, 9)),
+      vec![
+        at(2026, 1, 31, 9),
+        at(2026, 3, 31, 9),
+        at(2026, 5, 31, 9),
+        at(2026, 7, 31, 9),
+      ]
+    );
+  }
+
+  #[test]
+  fn monthly_fifth_weekday_skips_months_without_one() {
+    assert_eq!(
+      expand("FREQ=MONTHLY;BYDAY=5FR;COUNT=3", at(2026, 1, 30, 9)),
+      vec![at(2026, 1, 30, 9), at(2026, 5, 29, 9), at(2026, 7, 31, 9)]
+    );
+  }
+
+  #[test]
+  fn weekly_rule_expands_each_listed_day() {
+    assert_eq!(
+      expand(
+        "FREQ=WEEKLY;INTERVAL=2;BYDAY=WE,MO;COUNT=4",
+        at(2026, 1, 5, 9)
+      ),
+      vec![
+        at(2026, 1, 5, 9),
+        at(2026, 1, 7, 9),
+        at(2026, 1, 19, 9),
+        at(2026, 1, 21, 9),
+      ]
+    );
+  }
+
+  #[test]
+  fn count_starts_at_the_first_occurrence() {
+    let rule = parse_rrule(&rrule("FREQ=DAILY;COUNT=5")).unwrap();
+    let start = at(2026, 1, 1, 9);
+    assert_eq!(
+      occurrences(start, &rule, at(2026, 1, 3, 0), at(2026, 12, 31, 0)),
+      vec![at(2026, 1, 3, 9), at(2026, 1, 4, 9), at(2026, 1, 5, 9)]
+    );
+  }
+
+  #[test]
+  fn expansion_applies_exdate_and_overrides() {
+    let content = "BEGIN:VCALENDAR\r\n\
+      BEGIN:VEVENT\r\n\
+      UID:standup\r\n\
+      SUMMARY:Standup\r\n\
+      DTSTART:20260105T090000Z\r\n\
+      DURATION:PT15M\r\n\
+      RRULE:FREQ=DAILY;COUNT=3\r\n\
+      EXDATE:20260106T090000Z\r\n\
+      END:VEVENT\r\n\
+      BEGIN:VEVENT\r\n\
+      UID:standup\r\n\
+      RECURRENCE-ID:20260107T090000Z\r\n\
+      DTSTART:20260107T150000Z\r\n\
+      DTEND:20260107T151500Z\r\n\
+      END:VEVENT\r\n\
+      END:VCALENDAR\r\n";
+    let window = (
+      Utc.from_utc_datetime(&at(2026, 1, 1, 0)),
+      Utc.from_utc_datetime(&at(2026, 2, 1, 0)),
+    );
+    let events = expand_events(&parse_events(content), "source", window);
+    let starts: Vec<&str> = events.iter().map(|event| event.start_at.as_str()).collect();
+    assert_eq!(
+      starts,
+      vec!["2026-01-05T09:00:00+00:00", "2026-01-07T15:00:00+00:00"]
+    );
+    assert_eq!(events[0].end_at, "2026-01-05T09:15:00+00:00");
+    assert_eq!(events[0].title, "Standup");
+  }
+}
@@ -0,0 +1,25 @@
+mod export;
+pub mod feed;
+mod ics;
+pub mod import;
+pub mod planning;
+
+use crate::{state::AppState, storage::StorageError};
+
+pub use export::{build_calendar, ExportOptions};
+
+/// 读取当前数据并生成完整的 `.ics` 文本。
+pub fn render(state: &AppState, include_sessions: bool) -> Result<String, StorageError> {
+  let todos = state.store().load_todos()?;
+  let sessions = if include_sessions {
+    state.store().load_sessions()?
+  } else {
+    Vec::new()
+  };
+  let config = state.store().load_pomodoro_config()?;
+  let options = ExportOptions {
+    planned_block_minutes: config.focus_minutes,
+    include_sessions,
+  };
+  Ok(build_calendar(&todos, &sessions, &options))
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::storage::{CalendarEvent, TodoItem};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTodo {
+  pub todo: TodoItem,
+  pub start_at: String,
+  pub end_at: String,
+  /// 与该待办计划时间重叠的忙碌日程 id。
+  pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DayPlan {
+  pub date: String,
+  pub events: Vec<CalendarEvent>,
+  pub todos: Vec<PlannedTodo>,
+}
+
+/// 本地日期对应的 UTC 时间区间 `[start, end)`。
+pub fn local_day_bounds(date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+  let start = Local
+    .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+    .earliest()?
+    .with_timezone(&Utc);
+  let next = date.succ_opt()?;
+  let end = Local
+    .from_local_datetime(&next.and_hms_opt(0, 0, 0)?)
+    .earliest()?
+    .with_timezone(&Utc);
+  Some((start, end))
+}
+
+pub fn overlaps(a: (DateTime<Utc>, DateTime<Utc>), b: (DateTime<Utc>, DateTime<Utc>)) -> bool {
+  a.0 < b.1 && b.0 < a.1
+}
+
+/// 待办在 `planned_at` 起占用一个时间块，与忙碌的定时日程重叠即视为冲突。
+pub fn find_conflicts(
+  block: (DateTime<Utc>, DateTime<Utc>),
+  events: &[CalendarEvent],
+) -> Vec<String> {
+  events
+    .iter()
+    .filter(|event| event.busy && !event.all_day)
+    .filter(|event| match (event.start_time(), event.end_time()) {
+      (Some(start), Some(end)) => overlaps(block, (start, end.max(start + Duration::minutes(1)))),
+      _ => false,
+    })
+    .map(|event| event.id.clone())
+    .collect()
+}
+
+pub fn planned_block(
+  todo: &TodoItem,
+  block_minutes: u32,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+  let start = todo.planned_time()?;
+  Some((
+    start,
+    start + Duration::minutes(i64::from(block_minutes.max(1))),
+  ))
+}
+
+pub fn day_plan(
+  date: NaiveDate,
+  todos: &[TodoItem],
+  events: &[CalendarEvent],
+  block_minutes: u32,
+) -> Option<DayPlan> {
+  let day = local_day_bounds(date)?;
+  let mut day_events: Vec<CalendarEvent> = events
+    .iter()
+    .filter(|event| match (event.start_time(), event.end_time()) {
+      (Some(start), Some(end)) => overlaps(day, (start, end.max(start + Duration::minutes(1)))),
+      _ => false,
+    })
+    .cloned()
+    .collect();
+  day_events.sort_by_key(|event| event.start_time());
+
+  let mut planned: Vec<PlannedTodo> = todos
+    .iter()
+    .filter_map(|todo| {
+      let block = planned_block(todo, block_minutes)?;
+      if block.0 < day.0 || block.0 >= day.1 {
+        return None;
+      }
+      Some(PlannedTodo {
+        todo: todo.clone(),
+        start_at: block.0.to_rfc3339(),
+        end_at: block.1.to_rfc3339(),
+        conflicts: find_conflicts(block, &day_events),
+      })
+    })
+    .collect();
+  planned.sort_by(|a, b| a.start_at.cmp(&b.start_at));
+
+  Some(DayPlan {
+    date: date.format("%Y-%m-%d").to_string(),
+    events: day_events,
+    todos: planned,
+  })
+}
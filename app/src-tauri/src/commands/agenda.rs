@@ -0,0 +1,60 @@
+use chrono::Local;
+use tauri::{AppHandle, State};
+
+use crate::{
+  agenda::{self, Agenda, DayPlanning},
+  commands::{calendar::parse_date, CommandError},
+  i18n,
+  state::AppState,
+  storage::StorageError,
+  tray,
+};
+
+/// 某天（默认今天，本地时区）的逾期、今日截止、今日计划与近期待办。
+#[tauri::command]
+pub fn get_agenda(
+  state: State<AppState>,
+  date: Option<String>,
+  upcoming_days: Option<u32>,
+) -> Result<Agenda, CommandError> {
+  let date = match date {
+    Some(date) => parse_date(&date)?,
+    None => Local::now().date_naive(),
+  };
+  let todos = state.store().load_todos()?;
+  agenda::build(
+    date,
+    &todos,
+    upcoming_days.unwrap_or(agenda::DEFAULT_UPCOMING_DAYS),
+  )
+  .ok_or_else(|| CommandError::from(StorageError::validation(i18n::t!("date.outOfRange"))))
+}
+
+/// 规划某天：把选中的待办计划到当天，默认同时顺延前一天未完成的计划。
+#[tauri::command]
+pub fn plan_day(
+  app: AppHandle,
+  state: State<AppState>,
+  date: Option<String>,
+  todo_ids: Vec<String>,
+  roll_over: Option<bool>,
+) -> Result<DayPlanning, CommandError> {
+  let date = match date {
+    Some(date) => parse_date(&date)?,
+    None => Local::now().date_naive(),
+  };
+  let planning = state
+    .store()
+    .update_todos(|todos| agenda::plan(date, todos, &todo_ids, roll_over.unwrap_or(true)))?;
+  log::info!(
+    "已规划 {}: 计划 {} 项，顺延 {} 项",
+    date,
+    planning.planned.len(),
+    planning.rolled_over.len()
+  );
+  if let Err(err) = state.reminders().sync(&state) {
+    log::warn!("同步提醒失败: {}", err);
+  }
+  tray::refresh_todos(&app);
+  Ok(planning)
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use chrono::{Local, NaiveDate};
+use tauri::State;
+
+use crate::{
+  calendar::{self, planning::DayPlan},
+  commands::CommandError,
+  i18n,
+  state::AppState,
+  storage::{CalendarEvent, CalendarSource, StorageError},
+};
+
+/// 导出 iCalendar 文本；提供 `path` 时同时写入该文件。
+#[tauri::command]
+pub fn export_ics(
+  state: State<AppState>,
+  path: Option<String>,
+  include_sessions: Option<bool>,
+) -> Result<String, CommandError> {
+  let content = calendar::render(&state, include_sessions.unwrap_or(true))?;
+  if let Some(path) = path {
+    calendar::feed::write_atomic(Path::new(&path), content.as_bytes())?;
+  }
+  Ok(content)
+}
+
+/// 立即重新生成订阅文件，返回文件路径。
+#[tauri::command]
+pub fn refresh_ics_feed(state: State<AppState>) -> Result<String, CommandError> {
+  let settings = state.store().load_settings()?;
+  calendar::feed::write_feed(&state, &settings.ics_feed)
+    .map(|path| path.display().to_string())
+    .map_err(CommandError::from)
+}
+
+/// 导入外部 `.ics` 文件为只读日程；`watch` 为真时文件变化后自动重新导入。
+#[tauri::command]
+pub fn import_ics_file(
+  state: State<AppState>,
+  path: String,
+  watch: Option<bool>,
+) -> Result<CalendarSource, CommandError> {
+  calendar::import::import_file(&state, &path, watch).map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn list_calendar_sources(state: State<AppState>) -> Result<Vec<CalendarSource>, CommandError> {
+  state
+    .store()
+    .load_calendar()
+    .map(|calendar| calendar.sources)
+    .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn remove_calendar_source(state: State<AppState>, id: String) -> Result<(), CommandError> {
+  let mut calendar = state.store().load_calendar()?;
+  let initial_len = calendar.sources.len();
+  calendar.sources.retain(|source| source.id != id);
+  if calendar.sources.len() == initial_len {
+    return Err(StorageError::NotFound("calendar source").into());
+  }
+  calendar.events.retain(|event| event.source_id != id);
+  state
+    .store()
+    .save_calendar(&calendar)
+    .map_err(CommandError::from)
+}
+
+/// 列出与 `from`..=`to`（YYYY-MM-DD，本地日期）相交的外部日程。
+#[tauri::command]
+pub fn list_calendar_events(
+  state: State<AppState>,
+  from: String,
+  to: String,
+) -> Result<Vec<CalendarEvent>, CommandError> {
+  let from = parse_date(&from)?;
+  let to = parse_date(&to)?;
+  let (Some((start, _)), Some((_, end))) = (
+    calendar::planning::local_day_bounds(from),
+    calendar::planning::local_day_bounds(to),
+  ) else {
+    return Err(StorageError::validation(i18n::t!("date.outOfRange")).into());
+  };
+  let calendar = state.store().load_calendar()?;
+  Ok(
+    calendar
+      .events
+      .into_iter()
+      .filter(|event| match (event.start_time(), event.end_time()) {
+        (Some(event_start), Some(event_end)) => event_start < end && event_end >= start,
+        _ => false,
+      })
+      .collect(),
+  )
+}
+
+/// 某天的日程与计划待办，并标记与会议时间冲突的待办。
+#[tauri::command]
+pub fn get_day_plan(state: State<AppState>, date: Option<String>) -> Result<DayPlan, CommandError> {
+  let date = match date {
+    Some(date) => parse_date(&date)?,
+    None => Local::now().date_naive(),
+  };
+  let todos = state.store().load_todos()?;
+  let calendar = state.store().load_calendar()?;
+  let config = state.store().load_pomodoro_config()?;
+  calendar::planning::day_plan(date, &todos, &calendar.events, config.focus_minutes)
+    .ok_or_else(|| CommandError::from(StorageError::validation(i18n::t!("date.outOfRange"))))
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, CommandError> {
+  NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+    CommandError::from(StorageError::validation(i18n::t!(
+      "date.invalid",
+      value = value
+    )))
+  })
+}
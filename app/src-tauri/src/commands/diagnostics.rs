@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::{commands::CommandError, diagnostics, state::AppState, storage::StorageError};
+
+/// 导出用于反馈问题的诊断包（zip），返回文件路径；默认保存到应用数据目录的 `exports`。
+#[tauri::command]
+pub fn export_diagnostics(
+  app: AppHandle,
+  state: State<AppState>,
+  folder: Option<String>,
+) -> Result<String, CommandError> {
+  let folder = match folder {
+    Some(folder) if !folder.trim().is_empty() => PathBuf::from(folder),
+    _ => app
+      .path()
+      .app_data_dir()
+      .map_err(|_| CommandError::from(StorageError::ResolveDir))?
+      .join("exports"),
+  };
+  diagnostics::export(&app, &state, &folder)
+    .map(|path| path.display().to_string())
+    .map_err(CommandError::from)
+}
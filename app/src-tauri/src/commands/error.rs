@@ -0,0 +1,85 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::storage::StorageError;
+
+/// 稳定的错误码，前端据此区分错误类型，不依赖错误文案。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+  NotFound,
+  Validation,
+  Io,
+  CorruptData,
+  Window,
+  Internal,
+}
+
+/// 所有命令返回的错误：错误码、可直接展示的文案，以及可选的字段路径与附加信息。
+#[derive(Debug, Clone, Serialize, Error)]
+#[serde(rename_all = "camelCase")]
+#[error("{message}")]
+pub struct CommandError {
+  pub code: ErrorCode,
+  pub message: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub field: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub details: Option<Value>,
+}
+
+impl CommandError {
+  pub fn new<T: Into<String>>(code: ErrorCode, message: T) -> Self {
+    Self {
+      code,
+      message: message.into(),
+      field: None,
+      details: None,
+    }
+  }
+
+  pub fn internal<T: Into<String>>(message: T) -> Self {
+    Self::new(ErrorCode::Internal, message)
+  }
+
+  pub fn with_field<T: Into<String>>(mut self, field: T) -> Self {
+    self.field = Some(field.into());
+    self
+  }
+
+  pub fn with_details(mut self, details: Value) -> Self {
+    self.details = Some(details);
+    self
+  }
+}
+
+impl From<StorageError> for CommandError {
+  fn from(error: StorageError) -> Self {
+    let message = error.to_string();
+    match error {
+      StorageError::NotFound(entity) => {
+        Self::new(ErrorCode::NotFound, message).with_details(json!({ "entity": entity }))
+      }
+      StorageError::Validation { field, .. } => Self {
+        field,
+        ..Self::new(ErrorCode::Validation, message)
+      },
+      StorageError::File {
+        operation, path, ..
+      } => Self::new(ErrorCode::Io, message)
+        .with_details(json!({ "operation": operation, "path": path })),
+      StorageError::Corrupt { path, .. } => {
+        Self::new(ErrorCode::CorruptData, message).with_details(json!({ "path": path }))
+      }
+      StorageError::Io(_) | StorageError::ResolveDir => Self::new(ErrorCode::Io, message),
+      StorageError::Json(_) | StorageError::External(_) => Self::internal(message),
+    }
+  }
+}
+
+impl From<tauri::Error> for CommandError {
+  fn from(error: tauri::Error) -> Self {
+    Self::new(ErrorCode::Window, error.to_string())
+  }
+}
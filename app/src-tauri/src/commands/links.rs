@@ -0,0 +1,59 @@
+use tauri::State;
+
+use crate::{
+  commands::CommandError,
+  links,
+  state::AppState,
+  storage::{LinkKind, StorageError, TodoItem, TodoLink},
+};
+
+/// 创建 `from` 到 `to` 的关联；`blockedBy` 保存为方向相反的 `blocks`。
+#[tauri::command]
+pub fn link_todos(
+  state: State<AppState>,
+  from: String,
+  to: String,
+  kind: LinkKind,
+) -> Result<TodoLink, CommandError> {
+  let todos = state.store().load_todos()?;
+  let mut links = state.store().load_links()?;
+  let link = links::add(&todos, &mut links, kind, &from, &to)?;
+  state.store().save_links(&links)?;
+  log::debug!("已关联待办 {} -> {} ({:?})", link.from, link.to, link.kind);
+  Ok(link)
+}
+
+#[tauri::command]
+pub fn unlink_todos(state: State<AppState>, id: String) -> Result<(), CommandError> {
+  let mut links = state.store().load_links()?;
+  let initial_len = links.len();
+  links.retain(|link| link.id != id);
+  if links.len() == initial_len {
+    return Err(StorageError::NotFound("link").into());
+  }
+  state.store().save_links(&links).map_err(CommandError::from)
+}
+
+/// 列出关联；指定 `todo_id` 时只返回一端是该待办的关联。
+#[tauri::command]
+pub fn list_todo_links(
+  state: State<AppState>,
+  todo_id: Option<String>,
+) -> Result<Vec<TodoLink>, CommandError> {
+  let links = state.store().load_links()?;
+  Ok(match todo_id {
+    Some(todo_id) => links
+      .into_iter()
+      .filter(|link| link.from == todo_id || link.to == todo_id)
+      .collect(),
+    None => links,
+  })
+}
+
+/// 可以开始的待办：未完成，且阻塞它的待办都已完成。
+#[tauri::command]
+pub fn list_ready_todos(state: State<AppState>) -> Result<Vec<TodoItem>, CommandError> {
+  let todos = state.store().load_todos()?;
+  let links = state.store().load_links()?;
+  Ok(links::ready(&todos, &links).into_iter().cloned().collect())
+}
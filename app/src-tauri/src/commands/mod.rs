@@ -1,3 +1,15 @@
+pub mod agenda;
+pub mod calendar;
+pub mod diagnostics;
+mod error;
+pub mod links;
 pub mod pomodoro;
+pub mod reminders;
 pub mod settings;
+pub mod stats;
+pub mod timer;
+pub mod timesheet;
 pub mod todo;
+pub mod window;
+
+pub use error::{CommandError, ErrorCode};
@@ -1,30 +1,34 @@
 use chrono::{DateTime, Duration, NaiveDate};
-use tauri::State;
+use tauri::{AppHandle, State};
 use uuid::Uuid;
 
 use crate::{
+  commands::CommandError,
+  i18n,
   state::AppState,
   storage::{PomodoroConfig, PomodoroSession, PomodoroSessionDraft, StorageError},
+  timer,
 };
 
 #[tauri::command]
-pub fn get_pomodoro_config(state: State<AppState>) -> Result<PomodoroConfig, String> {
+pub fn get_pomodoro_config(state: State<AppState>) -> Result<PomodoroConfig, CommandError> {
   state
     .store()
     .load_pomodoro_config()
-    .map_err(to_string)
+    .map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn save_pomodoro_config(
+  app: AppHandle,
   state: State<AppState>,
   config: PomodoroConfig,
-) -> Result<PomodoroConfig, String> {
-  validate_config(&config).map_err(to_string)?;
-  state
-    .store()
-    .save_pomodoro_config(&config)
-    .map_err(to_string)?;
+) -> Result<PomodoroConfig, CommandError> {
+  validate_config(&config)?;
+  state.store().save_pomodoro_config(&config)?;
+  if let Some(snapshot) = state.timer().apply_config(&config) {
+    timer::publish(&app, &snapshot);
+  }
   Ok(config)
 }
 
@@ -32,11 +36,11 @@ pub fn save_pomodoro_config(
 pub fn append_pomodoro_session(
   state: State<AppState>,
   draft: PomodoroSessionDraft,
-) -> Result<PomodoroSession, String> {
+) -> Result<PomodoroSession, CommandError> {
   let session = build_session(draft)?;
-  let mut sessions = state.store().load_sessions().map_err(to_string)?;
+  let mut sessions = state.store().load_sessions()?;
   sessions.push(session.clone());
-  state.store().save_sessions(&sessions).map_err(to_string)?;
+  state.store().save_sessions(&sessions)?;
   Ok(session)
 }
 
@@ -44,16 +48,18 @@ pub fn append_pomodoro_session(
 pub fn list_pomodoro_sessions(
   state: State<AppState>,
   date: Option<String>,
-) -> Result<Vec<PomodoroSession>, String> {
-  let sessions = state.store().load_sessions().map_err(to_string)?;
+) -> Result<Vec<PomodoroSession>, CommandError> {
+  let sessions = state.store().load_sessions()?;
   if let Some(date_str) = date {
     if let Ok(target_date) = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
       let filtered = sessions
         .into_iter()
-        .filter(|session| match DateTime::parse_from_rfc3339(&session.start_at) {
-          Ok(dt) => dt.date_naive() == target_date,
-          Err(_) => false,
-        })
+        .filter(
+          |session| match DateTime::parse_from_rfc3339(&session.start_at) {
+            Ok(dt) => dt.date_naive() == target_date,
+            Err(_) => false,
+          },
+        )
         .collect();
       return Ok(filtered);
     }
@@ -67,22 +73,25 @@ fn validate_config(config: &PomodoroConfig) -> Result<(), StorageError> {
     || config.long_break_minutes == 0
     || config.long_break_interval == 0
   {
-    return Err(StorageError::validation("番茄钟配置参数必须大于 0"));
+    return Err(StorageError::validation(i18n::t!("pomodoro.nonPositive")));
   }
   if config.focus_minutes > 180 {
-    return Err(StorageError::validation("专注时长不建议超过 180 分钟"));
+    return Err(StorageError::invalid_field(
+      "focusMinutes",
+      i18n::t!("pomodoro.focusTooLong"),
+    ));
   }
   Ok(())
 }
 
-fn build_session(draft: PomodoroSessionDraft) -> Result<PomodoroSession, String> {
+fn build_session(draft: PomodoroSessionDraft) -> Result<PomodoroSession, CommandError> {
   let start = DateTime::parse_from_rfc3339(&draft.start_at)
-    .map_err(|_| StorageError::validation("开始时间格式错误").to_string())?;
+    .map_err(|_| CommandError::from(StorageError::validation(i18n::t!("time.invalidStart"))))?;
   let duration = match (&draft.end_at, draft.duration_minutes) {
     (Some(_), Some(explicit)) if explicit > 0 => Some(explicit),
     (Some(end_str), _) => {
       let end = DateTime::parse_from_rfc3339(end_str)
-        .map_err(|_| StorageError::validation("结束时间格式错误").to_string())?;
+        .map_err(|_| CommandError::from(StorageError::validation(i18n::t!("time.invalidEnd"))))?;
       let diff = end - start;
       Some(duration_minutes(diff))
     }
@@ -108,7 +117,3 @@ fn duration_minutes(duration: Duration) -> u32 {
     mins as u32
   }
 }
-
-fn to_string(error: StorageError) -> String {
-  error.to_string()
-}
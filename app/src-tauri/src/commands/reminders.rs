@@ -0,0 +1,103 @@
+use chrono::Utc;
+use tauri::{AppHandle, State};
+
+use crate::{
+  commands::CommandError,
+  i18n,
+  state::AppState,
+  storage::{ReminderOverride, ReminderRecord, StorageError, TodoItem},
+  tray,
+};
+
+const MAX_OFFSET_MINUTES: u32 = 30 * 24 * 60;
+
+#[tauri::command]
+pub fn list_reminders(
+  state: State<AppState>,
+  todo_id: Option<String>,
+) -> Result<Vec<ReminderRecord>, CommandError> {
+  let records = state.reminders().sync(&state)?;
+  Ok(match todo_id {
+    Some(todo_id) => records
+      .into_iter()
+      .filter(|record| record.todo_id == todo_id)
+      .collect(),
+    None => records,
+  })
+}
+
+#[tauri::command]
+pub fn snooze_reminder(
+  state: State<AppState>,
+  id: String,
+  minutes: u32,
+) -> Result<ReminderRecord, CommandError> {
+  state
+    .reminders()
+    .snooze(&state, &id, minutes)
+    .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn dismiss_reminder(
+  state: State<AppState>,
+  id: String,
+) -> Result<ReminderRecord, CommandError> {
+  state
+    .reminders()
+    .dismiss(&state, &id)
+    .map_err(CommandError::from)
+}
+
+/// 设置单个待办的提醒偏移（分钟）；传 `null` 恢复为全局设置。
+#[tauri::command]
+pub fn set_todo_reminders(
+  app: AppHandle,
+  state: State<AppState>,
+  todo_id: String,
+  reminder_override: Option<ReminderOverride>,
+) -> Result<TodoItem, CommandError> {
+  if let Some(overrides) = &reminder_override {
+    validate_offsets(
+      "dueOffsetsMinutes",
+      overrides.due_offsets_minutes.as_deref(),
+    )?;
+    validate_offsets(
+      "plannedOffsetsMinutes",
+      overrides.planned_offsets_minutes.as_deref(),
+    )?;
+  }
+  // 重复的偏移会生成相同的提醒 id，保存前去重。
+  let reminder_override = reminder_override.map(|overrides| ReminderOverride {
+    due_offsets_minutes: overrides.due_offsets_minutes.map(dedup_offsets),
+    planned_offsets_minutes: overrides.planned_offsets_minutes.map(dedup_offsets),
+  });
+  let updated = state.store().update_todos(|todos| {
+    let todo = todos
+      .iter_mut()
+      .find(|todo| todo.id == todo_id)
+      .ok_or(StorageError::NotFound("todo"))?;
+    todo.reminder_override = reminder_override;
+    todo.updated_at = Utc::now().to_rfc3339();
+    Ok(todo.clone())
+  })?;
+  state.reminders().sync(&state)?;
+  tray::refresh_todos(&app);
+  Ok(updated)
+}
+
+fn dedup_offsets(mut offsets: Vec<u32>) -> Vec<u32> {
+  offsets.sort_unstable();
+  offsets.dedup();
+  offsets
+}
+
+pub fn validate_offsets(field: &str, offsets: Option<&[u32]>) -> Result<(), StorageError> {
+  if offsets.is_some_and(|offsets| offsets.iter().any(|offset| *offset > MAX_OFFSET_MINUTES)) {
+    return Err(StorageError::invalid_field(
+      field,
+      i18n::t!("reminder.offsetTooLarge"),
+    ));
+  }
+  Ok(())
+}
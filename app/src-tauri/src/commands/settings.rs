@@ -1,50 +1,261 @@
-use tauri::State;
+use std::collections::BTreeSet;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, State};
+use uuid::Uuid;
 
 use crate::{
+  autostart, calendar,
+  commands::{self, CommandError},
+  hotkeys, i18n, logging,
   state::AppState,
   storage::{StorageError, UserSettings, WindowGeometry},
+  tray, windows, workflow,
 };
 
+/// 设置保存后广播给所有窗口，`keys` 为发生变化的字段路径，如 `hotkeys.skipPhase`。
+pub const SETTINGS_CHANGED_EVENT: &str = "settings:changed";
+
+const MIN_HTTP_API_PORT: u16 = 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsChangedPayload {
+  keys: Vec<String>,
+}
+
 #[tauri::command]
-pub fn get_settings(state: State<AppState>) -> Result<UserSettings, String> {
-  state
-    .store()
-    .load_settings()
-    .map_err(to_string)
+pub fn get_settings(state: State<AppState>) -> Result<UserSettings, CommandError> {
+  state.store().load_settings().map_err(CommandError::from)
 }
 
 #[tauri::command]
 pub fn save_settings(
+  app: AppHandle,
   state: State<AppState>,
   settings: UserSettings,
-) -> Result<UserSettings, String> {
-  state
-    .store()
-    .save_settings(&settings)
-    .map_err(to_string)?;
+) -> Result<UserSettings, CommandError> {
+  commit(&app, &state, |current| {
+    *current = settings;
+    Ok(())
+  })
+  .map_err(CommandError::from)
+}
+
+/// 按 JSON Merge Patch（RFC 7386）局部更新设置：对象逐层合并，`null` 删除字段（有默认值的字段随之恢复默认）。
+#[tauri::command]
+pub fn patch_settings(
+  app: AppHandle,
+  state: State<AppState>,
+  patch: Value,
+) -> Result<UserSettings, CommandError> {
+  if !patch.is_object() {
+    return Err(StorageError::validation(i18n::t!("settings.patchNotObject")).into());
+  }
+  commit(&app, &state, |current| {
+    let mut merged = serde_json::to_value(&*current)?;
+    merge_patch(&mut merged, &patch);
+    *current = serde_json::from_value(merged)
+      .map_err(|err| StorageError::validation(i18n::t!("settings.invalid", error = err)))?;
+    Ok(())
+  })
+  .map_err(CommandError::from)
+}
+
+#[tauri::command]
+pub fn regenerate_http_api_token(
+  app: AppHandle,
+  state: State<AppState>,
+) -> Result<UserSettings, CommandError> {
+  commit(&app, &state, |current| {
+    current.http_api.token = Some(generate_token());
+    Ok(())
+  })
+  .map_err(CommandError::from)
+}
+
+/// 在设置锁内修改、校验并保存设置，保存成功后再应用到运行时并广播变化的字段。
+///
+/// 应用失败时把设置文件和运行时状态回滚到修改前，并返回应用失败的错误。
+fn commit<F>(app: &AppHandle, state: &AppState, update: F) -> Result<UserSettings, StorageError>
+where
+  F: FnOnce(&mut UserSettings) -> Result<(), StorageError>,
+{
+  let mut previous = None;
+  let settings = state.store().update_settings(|settings| {
+    previous = Some(settings.clone());
+    update(settings)?;
+    if settings.http_api.enabled && settings.http_api.token.is_none() {
+      settings.http_api.token = Some(generate_token());
+    }
+    validate_settings(settings)
+  })?;
+  let Some(previous) = previous else {
+    return Ok(settings);
+  };
+
+  if let Err(err) = apply_runtime_settings(app, state, &settings) {
+    rollback(app, state, &previous, &settings);
+    return Err(err);
+  }
+
+  let mut keys = Vec::new();
+  changed_keys(
+    &serde_json::to_value(&previous)?,
+    &serde_json::to_value(&settings)?,
+    "",
+    &mut keys,
+  );
+  if keys.iter().any(|key| key == "language") {
+    tray::retranslate(app);
+  }
+  if !keys.is_empty() {
+    if let Err(err) = app.emit(SETTINGS_CHANGED_EVENT, SettingsChangedPayload { keys }) {
+      log::warn!("广播设置变化失败: {}", err);
+    }
+  }
   Ok(settings)
 }
 
+/// 运行时应用失败后恢复修改前的设置；期间设置已被其他修改覆盖时保留新的设置。
+fn rollback(app: &AppHandle, state: &AppState, previous: &UserSettings, failed: &UserSettings) {
+  let failed = serde_json::to_value(failed).ok();
+  let restored = state.store().update_settings(|current| {
+    if serde_json::to_value(&*current).ok() == failed {
+      *current = previous.clone();
+    }
+    Ok(())
+  });
+  match restored {
+    Ok(current) => {
+      if let Err(err) = apply_runtime_settings(app, state, &current) {
+        log::warn!("回滚运行时设置失败: {}", err);
+      }
+    }
+    Err(err) => log::warn!("回滚设置文件失败: {}", err),
+  }
+}
+
+/// 校验设置中的所有字段；主题等枚举值在反序列化时已经校验。
+pub fn validate_settings(settings: &UserSettings) -> Result<(), StorageError> {
+  commands::reminders::validate_offsets(
+    "reminders.dueOffsetsMinutes",
+    Some(&settings.reminders.due_offsets_minutes),
+  )?;
+  commands::reminders::validate_offsets(
+    "reminders.plannedOffsetsMinutes",
+    Some(&settings.reminders.planned_offsets_minutes),
+  )?;
+  windows::validate_behavior(settings)?;
+  hotkeys::validate(&settings.hotkeys)?;
+  workflow::validate(&settings.workflow)?;
+  if settings.http_api.port < MIN_HTTP_API_PORT {
+    return Err(StorageError::invalid_field(
+      "httpApi.port",
+      i18n::t!("settings.invalidPort", min = MIN_HTTP_API_PORT),
+    ));
+  }
+  if settings.ics_feed.interval_minutes == 0 {
+    return Err(StorageError::invalid_field(
+      "icsFeed.intervalMinutes",
+      i18n::t!("settings.invalidFeedInterval"),
+    ));
+  }
+  Ok(())
+}
+
+/// 将设置中需要后端生效的部分应用到运行时（日志级别、全局快捷键、开机启动、窗口行为、本地 HTTP API、日历订阅、界面语言等）。
+pub fn apply_runtime_settings(
+  app: &AppHandle,
+  state: &AppState,
+  settings: &UserSettings,
+) -> Result<(), StorageError> {
+  logging::set_level(settings.log_level);
+  state.hotkeys().apply(app, &settings.hotkeys)?;
+  autostart::apply(app, settings.autostart)?;
+  if let Err(err) = windows::apply_behavior(app, settings) {
+    log::warn!("应用窗口行为设置失败: {}", err);
+  }
+  state.http_api().apply(app, &settings.http_api)?;
+  if settings.ics_feed.enabled {
+    calendar::feed::write_feed(state, &settings.ics_feed)?;
+  }
+  i18n::set_language(settings.language);
+  Ok(())
+}
+
 #[tauri::command]
 pub fn record_window_state(
+  app: AppHandle,
   state: State<AppState>,
   label: String,
-  geometry: WindowGeometry,
-) -> Result<UserSettings, String> {
-  let mut settings = state.store().load_settings().map_err(to_string)?;
-  match label.as_str() {
-    "main" => settings.window_state.main = geometry,
-    "floating" => settings.window_state.floating = geometry,
-    other => {
-      return Err(
-        StorageError::validation(format!("未知窗口标签: {other}")).to_string()
-      )
+  mut geometry: WindowGeometry,
+) -> Result<UserSettings, CommandError> {
+  let Some(window) = app.get_webview_window(&label) else {
+    return Err(
+      StorageError::invalid_field("label", i18n::t!("window.unknownLabel", label = label)).into(),
+    );
+  };
+  if geometry.monitor.is_none() {
+    windows::annotate_geometry(&window, &mut geometry);
+  }
+  state
+    .store()
+    .update_settings(|settings| {
+      let key = windows::geometry_key(&label, settings);
+      settings.window_state.set(&key, geometry);
+      Ok(())
+    })
+    .map_err(CommandError::from)
+}
+
+/// 按 JSON Merge Patch（RFC 7386）把 `patch` 合并进 `target`，`null` 表示删除字段。
+pub(crate) fn merge_patch(target: &mut Value, patch: &Value) {
+  let Value::Object(patch) = patch else {
+    *target = patch.clone();
+    return;
+  };
+  if !target.is_object() {
+    *target = Value::Object(Map::new());
+  }
+  let Value::Object(target) = target else {
+    return;
+  };
+  for (key, value) in patch {
+    if value.is_null() {
+      target.remove(key);
+    } else {
+      merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
     }
   }
-  state.store().save_settings(&settings).map_err(to_string)?;
-  Ok(settings)
 }
 
-fn to_string(error: StorageError) -> String {
-  error.to_string()
+/// 比较前后两份设置，收集发生变化的叶子字段路径；数组整体视为一个值。
+fn changed_keys(before: &Value, after: &Value, prefix: &str, keys: &mut Vec<String>) {
+  match (before, after) {
+    (Value::Object(before), Value::Object(after)) => {
+      let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+      for name in names {
+        let path = if prefix.is_empty() {
+          name.clone()
+        } else {
+          format!("{prefix}.{name}")
+        };
+        let missing = Value::Null;
+        changed_keys(
+          before.get(name).unwrap_or(&missing),
+          after.get(name).unwrap_or(&missing),
+          &path,
+          keys,
+        );
+      }
+    }
+    (before, after) if before != after => keys.push(prefix.to_string()),
+    _ => {}
+  }
+}
+
+fn generate_token() -> String {
+  Uuid::new_v4().simple().to_string()
 }
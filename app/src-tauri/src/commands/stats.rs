@@ -0,0 +1,98 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate};
+use serde::Serialize;
+use tauri::State;
+
+use crate::{
+  commands::CommandError,
+  i18n,
+  state::AppState,
+  storage::{PomodoroSessionKind, StorageError},
+};
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyFocus {
+  pub date: String,
+  pub focus_minutes: u32,
+  pub focus_sessions: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusStats {
+  pub total_focus_minutes: u32,
+  pub focus_sessions: u32,
+  pub break_sessions: u32,
+  pub interrupted_sessions: u32,
+  pub completed_todos: u32,
+  pub days: Vec<DailyFocus>,
+}
+
+/// 统计 `from`..=`to`（YYYY-MM-DD）范围内的专注数据，缺省时不限制。
+#[tauri::command]
+pub fn get_focus_stats(
+  state: State<AppState>,
+  from: Option<String>,
+  to: Option<String>,
+) -> Result<FocusStats, CommandError> {
+  let from = parse_bound(from.as_deref())?;
+  let to = parse_bound(to.as_deref())?;
+  let in_range =
+    |date: NaiveDate| from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to);
+
+  let sessions = state.store().load_sessions()?;
+  let mut stats = FocusStats::default();
+  let mut days: BTreeMap<NaiveDate, DailyFocus> = BTreeMap::new();
+
+  for session in &sessions {
+    let Ok(start) = DateTime::parse_from_rfc3339(&session.start_at) else {
+      continue;
+    };
+    let date = start.date_naive();
+    if !in_range(date) {
+      continue;
+    }
+    if !session.completed {
+      stats.interrupted_sessions += 1;
+      continue;
+    }
+    if session.r#type != PomodoroSessionKind::Focus {
+      stats.break_sessions += 1;
+      continue;
+    }
+    let minutes = session.duration_minutes.unwrap_or(0);
+    stats.focus_sessions += 1;
+    stats.total_focus_minutes += minutes;
+    let day = days.entry(date).or_insert_with(|| DailyFocus {
+      date: date.format("%Y-%m-%d").to_string(),
+      ..DailyFocus::default()
+    });
+    day.focus_sessions += 1;
+    day.focus_minutes += minutes;
+  }
+
+  let todos = state.store().load_todos()?;
+  stats.completed_todos = todos
+    .iter()
+    .filter_map(|todo| todo.completed_at.as_deref())
+    .filter_map(|at| DateTime::parse_from_rfc3339(at).ok())
+    .filter(|at| in_range(at.date_naive()))
+    .count() as u32;
+  stats.days = days.into_values().collect();
+  Ok(stats)
+}
+
+fn parse_bound(value: Option<&str>) -> Result<Option<NaiveDate>, CommandError> {
+  value
+    .map(|raw| {
+      NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|_| {
+        CommandError::from(StorageError::validation(i18n::t!(
+          "date.invalid",
+          value = raw
+        )))
+      })
+    })
+    .transpose()
+}
@@ -0,0 +1,71 @@
+use tauri::{AppHandle, State};
+
+use crate::{
+  commands::CommandError,
+  state::AppState,
+  storage::PomodoroSessionKind,
+  timer::{self, TimerSnapshot},
+};
+
+#[tauri::command]
+pub fn get_timer_state(state: State<AppState>) -> Result<TimerSnapshot, CommandError> {
+  Ok(state.timer().snapshot())
+}
+
+#[tauri::command]
+pub fn start_timer(
+  app: AppHandle,
+  state: State<AppState>,
+  phase: Option<PomodoroSessionKind>,
+  todo_id: Option<String>,
+) -> Result<TimerSnapshot, CommandError> {
+  let config = state.store().load_pomodoro_config()?;
+  let (snapshot, interrupted) = state.timer().start(&config, phase, todo_id);
+  if let Some(draft) = interrupted {
+    timer::record_session(&app, draft);
+  }
+  timer::publish(&app, &snapshot);
+  Ok(snapshot)
+}
+
+#[tauri::command]
+pub fn pause_timer(app: AppHandle, state: State<AppState>) -> Result<TimerSnapshot, CommandError> {
+  let snapshot = state.timer().pause();
+  timer::publish(&app, &snapshot);
+  Ok(snapshot)
+}
+
+#[tauri::command]
+pub fn toggle_timer(app: AppHandle, state: State<AppState>) -> Result<TimerSnapshot, CommandError> {
+  let config = state.store().load_pomodoro_config()?;
+  let snapshot = state.timer().toggle(&config);
+  timer::publish(&app, &snapshot);
+  Ok(snapshot)
+}
+
+/// 重置当前阶段；传入 `phase` 时切换到该阶段，保持未开始。
+#[tauri::command]
+pub fn reset_timer(
+  app: AppHandle,
+  state: State<AppState>,
+  phase: Option<PomodoroSessionKind>,
+) -> Result<TimerSnapshot, CommandError> {
+  let config = state.store().load_pomodoro_config()?;
+  let (snapshot, interrupted) = state.timer().reset(&config, phase);
+  if let Some(draft) = interrupted {
+    timer::record_session(&app, draft);
+  }
+  timer::publish(&app, &snapshot);
+  Ok(snapshot)
+}
+
+#[tauri::command]
+pub fn skip_phase(app: AppHandle, state: State<AppState>) -> Result<TimerSnapshot, CommandError> {
+  let config = state.store().load_pomodoro_config()?;
+  let (snapshot, interrupted) = state.timer().skip(&config);
+  if let Some(draft) = interrupted {
+    timer::record_session(&app, draft);
+  }
+  timer::publish(&app, &snapshot);
+  Ok(snapshot)
+}
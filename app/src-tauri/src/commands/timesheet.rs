@@ -0,0 +1,75 @@
+use std::{fs, path::PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{
+  commands::{calendar::parse_date, CommandError},
+  i18n,
+  state::AppState,
+  storage::{FileOperation, StorageError},
+  timesheet::{self, Timesheet, TimesheetGrouping},
+};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimesheetExport {
+  pub timesheet: Timesheet,
+  pub csv_path: String,
+  pub markdown_path: String,
+}
+
+/// 导出 `from`..=`to`（YYYY-MM-DD）内的专注工时为 CSV 与 Markdown 表格。
+#[tauri::command]
+pub fn export_timesheet(
+  app: AppHandle,
+  state: State<AppState>,
+  from: String,
+  to: String,
+  group_by: Option<TimesheetGrouping>,
+  folder: Option<String>,
+) -> Result<TimesheetExport, CommandError> {
+  let from_date = parse_date(&from)?;
+  let to_date = parse_date(&to)?;
+  if from_date > to_date {
+    return Err(StorageError::validation(i18n::t!("date.rangeReversed")).into());
+  }
+
+  let settings = state.store().load_settings()?;
+  let todos = state.store().load_todos()?;
+  let sessions = state.store().load_sessions()?;
+  let grouping = group_by.unwrap_or_default();
+  let sheet = timesheet::build(
+    from_date,
+    to_date,
+    grouping,
+    &todos,
+    &sessions,
+    &settings.timesheet.rounding,
+  );
+
+  let folder = match folder.or(settings.timesheet.output_folder) {
+    Some(folder) if !folder.trim().is_empty() => PathBuf::from(folder),
+    _ => app
+      .path()
+      .app_data_dir()
+      .map_err(|_| CommandError::from(StorageError::ResolveDir))?
+      .join("exports"),
+  };
+  fs::create_dir_all(&folder).map_err(StorageError::file(FileOperation::CreateDir, &folder))?;
+
+  let stem = format!("timesheet_{}_{}", sheet.from, sheet.to);
+  let csv_path = folder.join(format!("{stem}.csv"));
+  let markdown_path = folder.join(format!("{stem}.md"));
+  // 带 BOM 便于 Excel 正确识别 UTF-8 中文。
+  let csv = format!("\u{feff}{}", timesheet::to_csv(&sheet));
+  fs::write(&csv_path, csv).map_err(StorageError::file(FileOperation::Write, &csv_path))?;
+  fs::write(&markdown_path, timesheet::to_markdown(&sheet))
+    .map_err(StorageError::file(FileOperation::Write, &markdown_path))?;
+
+  Ok(TimesheetExport {
+    timesheet: sheet,
+    csv_path: csv_path.display().to_string(),
+    markdown_path: markdown_path.display().to_string(),
+  })
+}
@@ -1,110 +1,303 @@
 use chrono::Utc;
-use tauri::State;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, State};
 use uuid::Uuid;
 
 use crate::{
+  commands::{settings::merge_patch, CommandError},
+  i18n, links, ordering,
   state::AppState,
-  storage::{StorageError, TodoDraft, TodoItem},
+  storage::{StorageError, TodoDraft, TodoItem, WorkflowSetting, WorkflowStatus},
+  tray, workflow,
 };
 
 #[tauri::command]
-pub fn list_todos(state: State<AppState>) -> Result<Vec<TodoItem>, String> {
-  state
-    .store()
-    .load_todos()
-    .map_err(to_string)
+pub fn list_todos(state: State<AppState>) -> Result<Vec<TodoItem>, CommandError> {
+  let mut todos = state.store().load_todos()?;
+  todos.sort_by(ordering::compare);
+  Ok(todos)
 }
 
 #[tauri::command]
-pub fn create_todo(state: State<AppState>, draft: TodoDraft) -> Result<TodoItem, String> {
-  let mut todos = state.store().load_todos().map_err(to_string)?;
+pub fn create_todo(
+  app: AppHandle,
+  state: State<AppState>,
+  draft: TodoDraft,
+) -> Result<TodoItem, CommandError> {
+  let workflow = state.store().load_settings()?.workflow;
   let now = Utc::now().to_rfc3339();
-  let todo = TodoItem {
+  let mut todo = TodoItem {
     id: Uuid::new_v4().to_string(),
     title: draft.title,
     detail: draft.detail,
     priority: draft.priority,
     tags: draft.tags,
+    project: draft.project,
     planned_at: draft.planned_at,
     due_at: draft.due_at,
     completed: false,
     completed_at: None,
+    reminder_override: None,
+    deferred_count: 0,
+    status: Some(workflow.initial),
+    status_history: Vec::new(),
+    sort_key: None,
     created_at: now.clone(),
     updated_at: now,
   };
-  todos.push(todo.clone());
-  state.store().save_todos(&todos).map_err(to_string)?;
+  let todo = state.store().update_todos(|todos| {
+    todo.sort_key = Some(ordering::append_key(todos, ordering::scope(&todo)));
+    todos.push(todo.clone());
+    Ok(todo)
+  })?;
+  log::debug!("已创建待办 {}", todo.id);
+  tray::refresh_todos(&app);
   Ok(todo)
 }
 
 #[tauri::command]
-pub fn update_todo(state: State<AppState>, updated: TodoItem) -> Result<TodoItem, String> {
-  let mut todos = state.store().load_todos().map_err(to_string)?;
-  let now = Utc::now().to_rfc3339();
+pub fn update_todo(
+  app: AppHandle,
+  state: State<AppState>,
+  updated: TodoItem,
+) -> Result<TodoItem, CommandError> {
+  let workflow = state.store().load_settings()?.workflow;
+  let (result, became_completed) = state
+    .store()
+    .update_todos(|todos| replace_todo(&workflow, todos, updated))?;
+  finish_update(&app, &state, &result, became_completed);
+  Ok(result)
+}
 
-  let mut found = false;
-  for todo in &mut todos {
-    if todo.id == updated.id {
-      *todo = TodoItem {
-        updated_at: now.clone(),
-        ..updated.clone()
-      };
-      found = true;
-      break;
-    }
+/// 按 JSON Merge Patch 修改待办，补丁中没有的字段保持原值。
+#[tauri::command]
+pub fn patch_todo(
+  app: AppHandle,
+  state: State<AppState>,
+  id: String,
+  patch: Value,
+) -> Result<TodoItem, CommandError> {
+  if !patch.is_object() {
+    return Err(StorageError::validation(i18n::t!("todo.patchNotObject")).into());
   }
+  let workflow = state.store().load_settings()?.workflow;
+  // 合并与写回在同一次加锁内完成，避免用过期的待办覆盖并发修改。
+  let (result, became_completed) = state.store().update_todos(|todos| {
+    let current = todos
+      .iter()
+      .find(|todo| todo.id == id)
+      .ok_or(StorageError::NotFound("todo"))?;
+    let mut merged = serde_json::to_value(current)?;
+    merge_patch(&mut merged, &patch);
+    let mut updated: TodoItem = serde_json::from_value(merged)
+      .map_err(|err| StorageError::validation(i18n::t!("todo.invalid", error = err)))?;
+    updated.id = id.clone();
+    replace_todo(&workflow, todos, updated)
+  })?;
+  finish_update(&app, &state, &result, became_completed);
+  Ok(result)
+}
 
-  if !found {
-    return Err(StorageError::NotFound("todo").to_string());
-  }
+/// 用客户端提交的待办替换已保存的待办，返回替换后的待办以及它是否刚被完成。
+fn replace_todo(
+  workflow: &WorkflowSetting,
+  todos: &mut [TodoItem],
+  updated: TodoItem,
+) -> Result<(TodoItem, bool), StorageError> {
+  let now = Utc::now().to_rfc3339();
+  let index = todos
+    .iter()
+    .position(|todo| todo.id == updated.id)
+    .ok_or(StorageError::NotFound("todo"))?;
+  // 排序键只能通过移动修改；换到其他项目时排到新项目的末尾。
+  let sort_key = if ordering::scope(&todos[index]) != ordering::scope(&updated) {
+    Some(ordering::append_key(todos, ordering::scope(&updated)))
+  } else {
+    todos[index].sort_key.clone()
+  };
 
-  state.store().save_todos(&todos).map_err(to_string)?;
+  let todo = &mut todos[index];
+  let became_completed = updated.completed && !todo.completed;
+  let completed = updated.completed;
+  // 状态、历史与完成时间只能通过状态切换修改，完成标记的变化换算成对应的状态切换。
+  let previous = std::mem::replace(
+    todo,
+    TodoItem {
+      updated_at: now.clone(),
+      ..updated
+    },
+  );
+  todo.status = previous.status;
+  todo.status_history = previous.status_history;
+  todo.completed = previous.completed;
+  todo.completed_at = previous.completed_at;
+  todo.sort_key = sort_key;
+  workflow::set_completed(workflow, todo, completed, &now)?;
+  Ok((todo.clone(), became_completed))
+}
 
-  todos
-    .into_iter()
-    .find(|item| item.id == updated.id)
-    .ok_or_else(|| StorageError::NotFound("todo").to_string())
+fn finish_update(app: &AppHandle, state: &AppState, todo: &TodoItem, became_completed: bool) {
+  if became_completed {
+    links::notify_unblocked(app, state, todo);
+  }
+  tray::refresh_todos(app);
 }
 
 #[tauri::command]
-pub fn delete_todo(state: State<AppState>, id: String) -> Result<(), String> {
-  let mut todos = state.store().load_todos().map_err(to_string)?;
-  let initial_len = todos.len();
-  todos.retain(|item| item.id != id);
-  if todos.len() == initial_len {
-    return Err(StorageError::NotFound("todo").to_string());
+pub fn delete_todo(app: AppHandle, state: State<AppState>, id: String) -> Result<(), CommandError> {
+  state.store().update_todos(|todos| {
+    let initial_len = todos.len();
+    todos.retain(|item| item.id != id);
+    if todos.len() == initial_len {
+      return Err(StorageError::NotFound("todo"));
+    }
+    Ok(())
+  })?;
+  let mut links = state.store().load_links()?;
+  if links::remove_todo(&mut links, &id) {
+    state.store().save_links(&links)?;
   }
-  state.store().save_todos(&todos).map_err(to_string)
+  log::debug!("已删除待办 {}", id);
+  tray::refresh_todos(&app);
+  Ok(())
 }
 
 #[tauri::command]
 pub fn toggle_complete(
+  app: AppHandle,
   state: State<AppState>,
   id: String,
   completed: bool,
-) -> Result<TodoItem, String> {
-  let mut todos = state.store().load_todos().map_err(to_string)?;
+) -> Result<TodoItem, CommandError> {
+  let workflow = state.store().load_settings()?.workflow;
   let now = Utc::now().to_rfc3339();
-  let mut result = None;
-
-  for todo in &mut todos {
-    if todo.id == id {
-      todo.completed = completed;
-      todo.completed_at = if completed { Some(now.clone()) } else { None };
-      todo.updated_at = now.clone();
-      result = Some(todo.clone());
-      break;
-    }
+  let (result, became_completed) = state.store().update_todos(|todos| {
+    let todo = todos
+      .iter_mut()
+      .find(|todo| todo.id == id)
+      .ok_or(StorageError::NotFound("todo"))?;
+    let became_completed = completed && !todo.completed;
+    workflow::set_completed(&workflow, todo, completed, &now)?;
+    Ok((todo.clone(), became_completed))
+  })?;
+  log::debug!("待办 {} 完成状态改为 {}", id, completed);
+  finish_update(&app, &state, &result, became_completed);
+  Ok(result)
+}
+
+/// 切换待办的工作流状态，只允许配置中列出的切换。
+#[tauri::command]
+pub fn transition_todo(
+  app: AppHandle,
+  state: State<AppState>,
+  id: String,
+  status: String,
+) -> Result<TodoItem, CommandError> {
+  let workflow = state.store().load_settings()?.workflow;
+  let (updated, became_completed) = state.store().update_todos(|todos| {
+    let todo = todos
+      .iter_mut()
+      .find(|todo| todo.id == id)
+      .ok_or(StorageError::NotFound("todo"))?;
+    let was_completed = todo.completed;
+    workflow::transition(&workflow, todo, &status, &Utc::now().to_rfc3339())?;
+    Ok((todo.clone(), todo.completed && !was_completed))
+  })?;
+  log::debug!("待办 {} 状态改为 {}", id, status);
+  finish_update(&app, &state, &updated, became_completed);
+  Ok(updated)
+}
+
+/// 待办当前状态允许切换到的状态。
+#[tauri::command]
+pub fn list_status_transitions(
+  state: State<AppState>,
+  id: String,
+) -> Result<Vec<WorkflowStatus>, CommandError> {
+  let workflow = state.store().load_settings()?.workflow;
+  let todos = state.store().load_todos()?;
+  let todo = todos
+    .iter()
+    .find(|todo| todo.id == id)
+    .ok_or(StorageError::NotFound("todo"))?;
+  Ok(workflow::allowed_transitions(&workflow, todo))
+}
+
+/// 把待办移动到同一列表中 `before` 与 `after` 之间，只改写被移动待办的排序键。
+#[tauri::command]
+pub fn move_todo(
+  app: AppHandle,
+  state: State<AppState>,
+  id: String,
+  before: Option<String>,
+  after: Option<String>,
+) -> Result<TodoItem, CommandError> {
+  let moved = state
+    .store()
+    .update_todos(|todos| ordering::move_todo(todos, &id, before.as_deref(), after.as_deref()))?;
+  log::debug!("待办 {} 排序键改为 {:?}", id, moved.sort_key);
+  if let Err(err) = app.emit(ordering::REORDERED_EVENT, &moved) {
+    log::warn!("广播排序事件失败: {}", err);
+  }
+  Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn todo(completed: bool, completed_at: Option<&str>) -> TodoItem {
+    serde_json::from_value(json!({
+      "id": "a",
+      "title": "写周报",
+      "status": if completed { "done" } else { "todo" },
+      "completed": completed,
+      "completedAt": completed_at,
+      "sortKey": "a0",
+      "createdAt": "2026-03-01T00:00:00Z",
+      "updatedAt": "2026-03-01T00:00:00Z",
+    }))
+    .unwrap()
   }
 
-  if result.is_none() {
-    return Err(StorageError::NotFound("todo").to_string());
+  #[test]
+  fn replace_ignores_client_completed_at() {
+    let workflow = WorkflowSetting::default();
+    let mut todos = vec![todo(false, None)];
+    let forged = TodoItem {
+      title: "改过的标题".to_string(),
+      ..todo(false, Some("2020-01-01T00:00:00Z"))
+    };
+    let (result, became_completed) = replace_todo(&workflow, &mut todos, forged).unwrap();
+    assert_eq!(result.title, "改过的标题");
+    assert_eq!(result.completed_at, None);
+    assert!(!became_completed);
+
+    let mut todos = vec![todo(true, Some("2026-03-01T08:00:00Z"))];
+    let forged = todo(true, Some("2020-01-01T00:00:00Z"));
+    let (result, _) = replace_todo(&workflow, &mut todos, forged).unwrap();
+    assert_eq!(result.completed_at.as_deref(), Some("2026-03-01T08:00:00Z"));
   }
 
-  state.store().save_todos(&todos).map_err(to_string)?;
-  Ok(result.unwrap())
-}
+  #[test]
+  fn replace_derives_completed_at_from_workflow() {
+    let workflow = WorkflowSetting::default();
+    let mut todos = vec![todo(false, None)];
+    let (result, became_completed) = replace_todo(
+      &workflow,
+      &mut todos,
+      todo(true, Some("2020-01-01T00:00:00Z")),
+    )
+    .unwrap();
+    assert!(became_completed);
+    assert_eq!(result.status.as_deref(), Some("done"));
+    assert_ne!(result.completed_at.as_deref(), Some("2020-01-01T00:00:00Z"));
+    assert!(result.completed_at.is_some());
 
-fn to_string(error: StorageError) -> String {
-  error.to_string()
+    let (result, _) = replace_todo(&workflow, &mut todos, todo(false, None)).unwrap();
+    assert_eq!(result.status.as_deref(), Some("todo"));
+    assert_eq!(result.completed_at, None);
+  }
 }
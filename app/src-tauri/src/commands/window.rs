@@ -0,0 +1,120 @@
+use tauri::{AppHandle, State};
+
+use crate::{
+  commands::{CommandError, ErrorCode},
+  i18n,
+  state::AppState,
+  storage::{FloatingMode, UserSettings, WindowPreference},
+  windows::{self, WindowKind},
+};
+
+#[tauri::command]
+pub fn toggle_window_visibility(app: AppHandle, label: String) -> Result<bool, CommandError> {
+  windows::toggle_window(&app, &label).map_err(|err| window_error(&label, err))
+}
+
+#[tauri::command]
+pub fn show_window(app: AppHandle, label: String) -> Result<(), CommandError> {
+  windows::show_window(&app, &label).map_err(|err| window_error(&label, err))
+}
+
+#[tauri::command]
+pub fn hide_window(app: AppHandle, label: String) -> Result<(), CommandError> {
+  windows::hide_window(&app, &label).map_err(|err| window_error(&label, err))
+}
+
+/// 坐标为物理像素，与 `outerPosition()` 返回值一致。
+#[tauri::command]
+pub fn set_window_position(
+  app: AppHandle,
+  label: String,
+  x: i32,
+  y: i32,
+) -> Result<(), CommandError> {
+  windows::set_window_position(&app, &label, x, y).map_err(|err| window_error(&label, err))
+}
+
+/// 按类型打开窗口并返回其标签；项目悬浮窗需要传入项目名。
+///
+/// 必须是异步命令：在主线程上同步创建窗口会在 Windows 上死锁。
+#[tauri::command]
+pub async fn open_window(
+  app: AppHandle,
+  kind: WindowKind,
+  project: Option<String>,
+) -> Result<String, CommandError> {
+  let label = kind.label(project.as_deref())?;
+  windows::open_window(&app, kind, &label, project.as_deref())
+    .map_err(|err| window_error(&label, err))?;
+  Ok(label)
+}
+
+#[tauri::command]
+pub fn focus_window(
+  app: AppHandle,
+  kind: WindowKind,
+  project: Option<String>,
+) -> Result<(), CommandError> {
+  let label = kind.label(project.as_deref())?;
+  windows::focus_window(&app, &label).map_err(|err| window_error(&label, err))
+}
+
+#[tauri::command]
+pub fn close_window(
+  app: AppHandle,
+  kind: WindowKind,
+  project: Option<String>,
+) -> Result<(), CommandError> {
+  let label = kind.label(project.as_deref())?;
+  windows::close_window(&app, kind, &label).map_err(|err| window_error(&label, err))
+}
+
+/// 保存某个窗口的显示偏好，窗口已打开时立即生效。
+#[tauri::command]
+pub fn set_window_preference(
+  app: AppHandle,
+  state: State<AppState>,
+  kind: WindowKind,
+  project: Option<String>,
+  preference: WindowPreference,
+) -> Result<UserSettings, CommandError> {
+  let label = kind.label(project.as_deref())?;
+  let settings = state.store().update_settings(|settings| {
+    settings
+      .window_preferences
+      .insert(label.clone(), preference);
+    Ok(())
+  })?;
+  windows::apply_preference(&app, &label, &settings).map_err(|err| window_error(&label, err))?;
+  Ok(settings)
+}
+
+/// 切换悬浮窗的紧凑/展开模式，两种模式各自记住尺寸与位置。
+#[tauri::command]
+pub fn set_floating_mode(app: AppHandle, mode: FloatingMode) -> Result<FloatingMode, CommandError> {
+  windows::set_floating_mode(&app, mode)?;
+  Ok(mode)
+}
+
+#[tauri::command]
+pub fn toggle_floating_mode(
+  app: AppHandle,
+  state: State<AppState>,
+) -> Result<FloatingMode, CommandError> {
+  let mode = match state.store().load_settings()?.floating_mode {
+    FloatingMode::Compact => FloatingMode::Expanded,
+    FloatingMode::Expanded => FloatingMode::Compact,
+  };
+  set_floating_mode(app, mode)
+}
+
+fn window_error(label: &str, error: tauri::Error) -> CommandError {
+  match error {
+    tauri::Error::WindowNotFound => CommandError::new(
+      ErrorCode::NotFound,
+      i18n::t!("window.unknownLabel", label = label),
+    )
+    .with_field("label"),
+    other => other.into(),
+  }
+}
@@ -0,0 +1,181 @@
+use std::{
+  fs::{self, File},
+  io::{self, Write},
+  path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+  i18n, logging,
+  state::AppState,
+  storage::{FileOperation, StorageError},
+};
+
+const REDACTED: &str = "<redacted>";
+/// 设置中可能包含密钥或本机路径的字段（JSON Pointer）。
+const SENSITIVE_SETTINGS: [&str; 3] = [
+  "/httpApi/token",
+  "/icsFeed/folder",
+  "/timesheet/outputFolder",
+];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+  app_name: String,
+  app_version: String,
+  tauri_version: &'static str,
+  os: &'static str,
+  arch: &'static str,
+  debug_build: bool,
+  generated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileStat {
+  name: String,
+  bytes: u64,
+  modified_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DataStats {
+  files: Vec<FileStat>,
+  backups: Vec<FileStat>,
+  todos: usize,
+  sessions: usize,
+  reminders: usize,
+  links: usize,
+  calendar_events: usize,
+}
+
+/// 打包诊断信息：版本与系统信息、脱敏后的设置、数据文件统计以及全部日志文件。
+pub fn export(app: &AppHandle, state: &AppState, folder: &Path) -> Result<PathBuf, StorageError> {
+  fs::create_dir_all(folder).map_err(StorageError::file(FileOperation::CreateDir, folder))?;
+  let path = folder.join(format!(
+    "diagnostics_{}.zip",
+    Local::now().format("%Y%m%d_%H%M%S")
+  ));
+  let mut zip =
+    ZipWriter::new(File::create(&path).map_err(StorageError::file(FileOperation::Write, &path))?);
+  let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+  let package = app.package_info();
+  let manifest = Manifest {
+    app_name: package.name.clone(),
+    app_version: package.version.to_string(),
+    tauri_version: tauri::VERSION,
+    os: std::env::consts::OS,
+    arch: std::env::consts::ARCH,
+    debug_build: cfg!(debug_assertions),
+    generated_at: Utc::now().to_rfc3339(),
+  };
+  write_json(&mut zip, &path, "manifest.json", &manifest, options)?;
+  write_json(
+    &mut zip,
+    &path,
+    "settings.json",
+    &redacted_settings(state)?,
+    options,
+  )?;
+  write_json(
+    &mut zip,
+    &path,
+    "data-stats.json",
+    &data_stats(state)?,
+    options,
+  )?;
+
+  let log_dir = logging::log_dir(app)?;
+  for stat in list_files(&log_dir)? {
+    zip
+      .start_file(format!("logs/{}", stat.name), options)
+      .map_err(zip_error)?;
+    let log_path = log_dir.join(&stat.name);
+    let mut log_file =
+      File::open(&log_path).map_err(StorageError::file(FileOperation::Read, &log_path))?;
+    io::copy(&mut log_file, &mut zip).map_err(StorageError::file(FileOperation::Write, &path))?;
+  }
+
+  zip.finish().map_err(zip_error)?;
+  log::info!("诊断包已导出: {}", path.display());
+  Ok(path)
+}
+
+fn redacted_settings(state: &AppState) -> Result<Value, StorageError> {
+  let mut settings = serde_json::to_value(state.store().load_settings()?)?;
+  for pointer in SENSITIVE_SETTINGS {
+    if let Some(value) = settings
+      .pointer_mut(pointer)
+      .filter(|value| !value.is_null())
+    {
+      *value = Value::String(REDACTED.to_string());
+    }
+  }
+  Ok(settings)
+}
+
+fn data_stats(state: &AppState) -> Result<DataStats, StorageError> {
+  let store = state.store();
+  let data_dir = store.data_dir();
+  Ok(DataStats {
+    files: list_files(data_dir)?,
+    backups: list_files(&data_dir.join("backups"))?,
+    todos: store.load_todos()?.len(),
+    sessions: store.load_sessions()?.len(),
+    reminders: store.load_reminders()?.len(),
+    links: store.load_links()?.len(),
+    calendar_events: store.load_calendar()?.events.len(),
+  })
+}
+
+/// 目录下的普通文件（不递归），按文件名排序；目录不存在时为空。
+fn list_files(dir: &Path) -> Result<Vec<FileStat>, StorageError> {
+  if !dir.is_dir() {
+    return Ok(Vec::new());
+  }
+  let mut files = Vec::new();
+  let read_error = || StorageError::file(FileOperation::Read, dir);
+  for entry in fs::read_dir(dir).map_err(read_error())? {
+    let entry = entry.map_err(read_error())?;
+    let metadata = entry.metadata().map_err(read_error())?;
+    if !metadata.is_file() {
+      continue;
+    }
+    files.push(FileStat {
+      name: entry.file_name().to_string_lossy().into_owned(),
+      bytes: metadata.len(),
+      modified_at: metadata
+        .modified()
+        .ok()
+        .map(|time| DateTime::<Utc>::from(time).to_rfc3339()),
+    });
+  }
+  files.sort_by(|a, b| a.name.cmp(&b.name));
+  Ok(files)
+}
+
+fn write_json<T: Serialize>(
+  zip: &mut ZipWriter<File>,
+  path: &Path,
+  name: &str,
+  value: &T,
+  options: SimpleFileOptions,
+) -> Result<(), StorageError> {
+  zip.start_file(name, options).map_err(zip_error)?;
+  zip
+    .write_all(&serde_json::to_vec_pretty(value)?)
+    .map_err(StorageError::file(FileOperation::Write, path))?;
+  Ok(())
+}
+
+fn zip_error(error: zip::result::ZipError) -> StorageError {
+  StorageError::External(i18n::t!("diagnostics.zipFailed", error = error))
+}
@@ -0,0 +1,211 @@
+use std::{str::FromStr, thread};
+
+use parking_lot::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::{
+  commands::{self, CommandError},
+  i18n,
+  state::AppState,
+  storage::{HotkeySetting, StorageError},
+  windows::{self, WindowKind},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HotkeyAction {
+  ToggleFloating,
+  StartOrPauseTimer,
+  QuickAddTodo,
+  SkipPhase,
+  ShowMainWindow,
+}
+
+impl HotkeyAction {
+  fn field(self) -> &'static str {
+    match self {
+      HotkeyAction::ToggleFloating => "hotkeys.toggleFloating",
+      HotkeyAction::StartOrPauseTimer => "hotkeys.startOrPauseTimer",
+      HotkeyAction::QuickAddTodo => "hotkeys.quickAddTodo",
+      HotkeyAction::SkipPhase => "hotkeys.skipPhase",
+      HotkeyAction::ShowMainWindow => "hotkeys.showMainWindow",
+    }
+  }
+}
+
+#[derive(Clone, Copy)]
+struct Binding {
+  action: HotkeyAction,
+  shortcut: Shortcut,
+}
+
+/// 全局快捷键注册表：记录当前生效的绑定，设置变化时整体重新注册。
+#[derive(Default)]
+pub struct HotkeyRegistry {
+  guard: Mutex<()>,
+  active: Mutex<Vec<Binding>>,
+}
+
+impl HotkeyRegistry {
+  /// 校验并注册设置中的快捷键；任一注册失败时恢复原有绑定并返回校验错误。
+  pub fn apply(&self, app: &AppHandle, setting: &HotkeySetting) -> Result<(), StorageError> {
+    let _lock = self.guard.lock();
+    let bindings = parse(setting)?;
+    let previous = self.active.lock().clone();
+    if same_bindings(&previous, &bindings) {
+      return Ok(());
+    }
+
+    // 注册过程会切到主线程执行，期间不能持有 `active`，否则快捷键回调会死锁。
+    unregister(app, &previous);
+    if let Err(err) = register(app, &bindings) {
+      if let Err(restore) = register(app, &previous) {
+        log::warn!("恢复原有快捷键失败: {}", restore);
+      }
+      return Err(err);
+    }
+    *self.active.lock() = bindings;
+    log::info!("全局快捷键已更新");
+    Ok(())
+  }
+
+  fn action_for(&self, shortcut: &Shortcut) -> Option<HotkeyAction> {
+    self
+      .active
+      .lock()
+      .iter()
+      .find(|binding| binding.shortcut.id() == shortcut.id())
+      .map(|binding| binding.action)
+  }
+}
+
+/// 只校验快捷键格式与重复，不注册。
+pub fn validate(setting: &HotkeySetting) -> Result<(), StorageError> {
+  parse(setting).map(|_| ())
+}
+
+/// 解析快捷键字符串，空值表示未绑定；格式错误或重复绑定时返回校验错误。
+fn parse(setting: &HotkeySetting) -> Result<Vec<Binding>, StorageError> {
+  let entries = [
+    (HotkeyAction::ToggleFloating, &setting.toggle_floating),
+    (
+      HotkeyAction::StartOrPauseTimer,
+      &setting.start_or_pause_timer,
+    ),
+    (HotkeyAction::QuickAddTodo, &setting.quick_add_todo),
+    (HotkeyAction::SkipPhase, &setting.skip_phase),
+    (HotkeyAction::ShowMainWindow, &setting.show_main_window),
+  ];
+
+  let mut bindings: Vec<Binding> = Vec::new();
+  for (action, accelerator) in entries {
+    let Some(accelerator) = accelerator
+      .as_deref()
+      .map(str::trim)
+      .filter(|value| !value.is_empty())
+    else {
+      continue;
+    };
+    let shortcut = Shortcut::from_str(accelerator).map_err(|err| {
+      StorageError::invalid_field(
+        action.field(),
+        i18n::t!(
+          "hotkey.invalid",
+          field = action.field(),
+          accelerator = accelerator,
+          error = err
+        ),
+      )
+    })?;
+    if let Some(existing) = bindings
+      .iter()
+      .find(|binding| binding.shortcut.id() == shortcut.id())
+    {
+      return Err(StorageError::invalid_field(
+        action.field(),
+        i18n::t!(
+          "hotkey.conflict",
+          accelerator = accelerator,
+          first = existing.action.field(),
+          second = action.field()
+        ),
+      ));
+    }
+    bindings.push(Binding { action, shortcut });
+  }
+  Ok(bindings)
+}
+
+fn same_bindings(a: &[Binding], b: &[Binding]) -> bool {
+  a.len() == b.len()
+    && a
+      .iter()
+      .zip(b)
+      .all(|(x, y)| x.action == y.action && x.shortcut.id() == y.shortcut.id())
+}
+
+fn register(app: &AppHandle, bindings: &[Binding]) -> Result<(), StorageError> {
+  let manager = app.global_shortcut();
+  for (index, binding) in bindings.iter().enumerate() {
+    if let Err(err) = manager.register(binding.shortcut) {
+      unregister(app, &bindings[..index]);
+      return Err(StorageError::invalid_field(
+        binding.action.field(),
+        i18n::t!(
+          "hotkey.registerFailed",
+          shortcut = binding.shortcut,
+          field = binding.action.field(),
+          error = err
+        ),
+      ));
+    }
+  }
+  Ok(())
+}
+
+fn unregister(app: &AppHandle, bindings: &[Binding]) {
+  let manager = app.global_shortcut();
+  for binding in bindings {
+    if let Err(err) = manager.unregister(binding.shortcut) {
+      log::warn!("注销快捷键 {} 失败: {}", binding.shortcut, err);
+    }
+  }
+}
+
+/// 全局快捷键插件的回调入口，只在按下时触发一次动作。
+pub fn handle(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+  if event.state != ShortcutState::Pressed {
+    return;
+  }
+  let state = app.state::<AppState>();
+  let Some(action) = state.hotkeys().action_for(shortcut) else {
+    return;
+  };
+  if let Err(err) = run(app, action) {
+    log::warn!("执行快捷键动作 {:?} 失败: {}", action, err);
+  }
+}
+
+fn run(app: &AppHandle, action: HotkeyAction) -> Result<(), CommandError> {
+  match action {
+    HotkeyAction::ToggleFloating => windows::toggle_window(app, "floating")
+      .map(|_| ())
+      .map_err(CommandError::from),
+    HotkeyAction::StartOrPauseTimer => {
+      commands::timer::toggle_timer(app.clone(), app.state()).map(|_| ())
+    }
+    HotkeyAction::SkipPhase => commands::timer::skip_phase(app.clone(), app.state()).map(|_| ()),
+    HotkeyAction::ShowMainWindow => windows::show_window(app, "main").map_err(CommandError::from),
+    HotkeyAction::QuickAddTodo => {
+      let label = WindowKind::QuickAdd.label(None)?;
+      let app = app.clone();
+      // 快捷键回调在主线程上，在这里同步创建窗口会在 Windows 上死锁。
+      thread::spawn(move || {
+        if let Err(err) = windows::open_window(&app, WindowKind::QuickAdd, &label, None) {
+          log::warn!("打开快速新建窗口失败: {}", err);
+        }
+      });
+      Ok(())
+    }
+  }
+}
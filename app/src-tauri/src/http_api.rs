@@ -0,0 +1,357 @@
+use std::{
+  io::{Cursor, Read},
+  sync::Arc,
+  thread::{self, JoinHandle},
+};
+
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{
+  commands::{self, CommandError, ErrorCode},
+  i18n,
+  storage::{HttpApiSetting, PomodoroSessionKind, StorageError},
+};
+
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+type ApiResponse = Response<Cursor<Vec<u8>>>;
+
+struct RunningServer {
+  server: Arc<Server>,
+  port: u16,
+  token: String,
+  worker: Option<JoinHandle<()>>,
+}
+
+/// 仅监听回环地址的本地 HTTP/JSON API，所有接口都复用 `commands::*` 的实现。
+#[derive(Default)]
+pub struct HttpApiServer {
+  running: Mutex<Option<RunningServer>>,
+}
+
+impl HttpApiServer {
+  /// 按设置启动、重启或停止服务；端口与令牌未变化时保持现有监听。
+  pub fn apply(&self, app: &AppHandle, setting: &HttpApiSetting) -> Result<(), StorageError> {
+    let token = setting.token.clone().unwrap_or_default();
+    if !setting.enabled {
+      self.stop();
+      return Ok(());
+    }
+    if token.trim().is_empty() {
+      return Err(StorageError::validation(i18n::t!("httpApi.tokenRequired")));
+    }
+
+    let mut running = self.running.lock();
+    if let Some(current) = running.as_ref() {
+      if current.port == setting.port && current.token == token {
+        return Ok(());
+      }
+    }
+    if let Some(previous) = running.take() {
+      shutdown(previous);
+    }
+
+    let server = Server::http(("127.0.0.1", setting.port))
+      .map(Arc::new)
+      .map_err(|err| {
+        StorageError::validation(i18n::t!(
+          "httpApi.bindFailed",
+          port = setting.port,
+          error = err
+        ))
+      })?;
+    let worker = {
+      let server = server.clone();
+      let app = app.clone();
+      let token = token.clone();
+      thread::spawn(move || {
+        for request in server.incoming_requests() {
+          handle(&app, &token, request);
+        }
+      })
+    };
+    log::info!("HTTP API 已监听 127.0.0.1:{}", setting.port);
+    *running = Some(RunningServer {
+      server,
+      port: setting.port,
+      token,
+      worker: Some(worker),
+    });
+    Ok(())
+  }
+
+  pub fn stop(&self) {
+    if let Some(previous) = self.running.lock().take() {
+      shutdown(previous);
+    }
+  }
+}
+
+fn shutdown(mut running: RunningServer) {
+  running.server.unblock();
+  if let Some(worker) = running.worker.take() {
+    let _ = worker.join();
+  }
+  log::info!("HTTP API 已停止监听端口 {}", running.port);
+}
+
+fn handle(app: &AppHandle, token: &str, mut request: Request) {
+  let response = if authorized(&request, token) {
+    route(app, &mut request)
+  } else {
+    error_response(401, i18n::t!("httpApi.unauthorized"))
+  };
+  let _ = request.respond(response);
+}
+
+fn authorized(request: &Request, token: &str) -> bool {
+  request.headers().iter().any(|header| {
+    let value = header.value.as_str();
+    if header.field.equiv("Authorization") {
+      value
+        .strip_prefix("Bearer ")
+        .is_some_and(|candidate| constant_time_eq(candidate.trim(), token))
+    } else if header.field.equiv("X-FocusTodo-Token") {
+      constant_time_eq(value.trim(), token)
+    } else {
+      false
+    }
+  })
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+  if a.len() != b.len() {
+    return false;
+  }
+  a.bytes()
+    .zip(b.bytes())
+    .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+    == 0
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompleteBody {
+  #[serde(default = "default_completed")]
+  completed: bool,
+}
+
+fn default_completed() -> bool {
+  true
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartTimerBody {
+  #[serde(default)]
+  phase: Option<PomodoroSessionKind>,
+  #[serde(default)]
+  todo_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ResetTimerBody {
+  #[serde(default)]
+  phase: Option<PomodoroSessionKind>,
+}
+
+enum ApiError {
+  BadRequest(String),
+  Command(CommandError),
+  NotFound,
+  MethodNotAllowed,
+}
+
+impl From<CommandError> for ApiError {
+  fn from(error: CommandError) -> Self {
+    ApiError::Command(error)
+  }
+}
+
+fn route(app: &AppHandle, request: &mut Request) -> ApiResponse {
+  let url = request.url().to_string();
+  let (path, query) = url.split_once('?').unwrap_or((url.as_str(), ""));
+  let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+  let method = request.method().clone();
+
+  let result = match (segments.as_slice(), &method) {
+    (["api", "todos"], Method::Get) => ok(commands::todo::list_todos(app.state())),
+    (["api", "todos"], Method::Post) => read_body(request)
+      .and_then(|draft| ok(commands::todo::create_todo(app.clone(), app.state(), draft))),
+    (["api", "todos", id], Method::Put | Method::Patch) => read_body(request).and_then(|patch| {
+      ok(commands::todo::patch_todo(
+        app.clone(),
+        app.state(),
+        id.to_string(),
+        patch,
+      ))
+    }),
+    (["api", "todos", id], Method::Delete) => ok(commands::todo::delete_todo(
+      app.clone(),
+      app.state(),
+      id.to_string(),
+    )),
+    (["api", "todos", id, "complete"], Method::Post) => {
+      read_body_or_default::<CompleteBody>(request).and_then(|body| {
+        ok(commands::todo::toggle_complete(
+          app.clone(),
+          app.state(),
+          id.to_string(),
+          body.completed,
+        ))
+      })
+    }
+    (["api", "sessions"], Method::Get) => ok(commands::pomodoro::list_pomodoro_sessions(
+      app.state(),
+      query_param(query, "date"),
+    )),
+    (["api", "sessions"], Method::Post) => read_body(request).and_then(|draft| {
+      ok(commands::pomodoro::append_pomodoro_session(
+        app.state(),
+        draft,
+      ))
+    }),
+    (["api", "timer"], Method::Get) => ok(commands::timer::get_timer_state(app.state())),
+    (["api", "timer", action], Method::Post) => timer_action(app, request, action),
+    (["api", "stats"], Method::Get) => ok(commands::stats::get_focus_stats(
+      app.state(),
+      query_param(query, "from"),
+      query_param(query, "to"),
+    )),
+    (["api", "todos"], _)
+    | (["api", "todos", _], _)
+    | (["api", "todos", _, "complete"], _)
+    | (["api", "sessions"], _)
+    | (["api", "timer"], _)
+    | (["api", "timer", _], _)
+    | (["api", "stats"], _) => Err(ApiError::MethodNotAllowed),
+    _ => Err(ApiError::NotFound),
+  };
+
+  match result {
+    Ok(value) => json_response(200, &value),
+    Err(ApiError::BadRequest(message)) => error_response(400, message),
+    Err(ApiError::Command(error)) => command_error_response(error),
+    Err(ApiError::NotFound) => error_response(404, i18n::t!("httpApi.unknownRoute", path = path)),
+    Err(ApiError::MethodNotAllowed) => {
+      error_response(405, i18n::t!("httpApi.methodNotAllowed", method = method))
+    }
+  }
+}
+
+fn timer_action(app: &AppHandle, request: &mut Request, action: &str) -> Result<Value, ApiError> {
+  match action {
+    "start" => read_body_or_default::<StartTimerBody>(request).and_then(|body| {
+      ok(commands::timer::start_timer(
+        app.clone(),
+        app.state(),
+        body.phase,
+        body.todo_id,
+      ))
+    }),
+    "pause" => ok(commands::timer::pause_timer(app.clone(), app.state())),
+    "toggle" => ok(commands::timer::toggle_timer(app.clone(), app.state())),
+    "reset" => read_body_or_default::<ResetTimerBody>(request).and_then(|body| {
+      ok(commands::timer::reset_timer(
+        app.clone(),
+        app.state(),
+        body.phase,
+      ))
+    }),
+    "skip" => ok(commands::timer::skip_phase(app.clone(), app.state())),
+    _ => Err(ApiError::NotFound),
+  }
+}
+
+fn ok<T: Serialize>(result: Result<T, CommandError>) -> Result<Value, ApiError> {
+  let value = result?;
+  serde_json::to_value(value).map_err(|err| ApiError::BadRequest(err.to_string()))
+}
+
+fn read_body<T: DeserializeOwned>(request: &mut Request) -> Result<T, ApiError> {
+  let mut body = String::new();
+  request
+    .as_reader()
+    .take(MAX_BODY_BYTES)
+    .read_to_string(&mut body)
+    .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+  serde_json::from_str(&body)
+    .map_err(|err| ApiError::BadRequest(i18n::t!("httpApi.invalidBody", error = err)))
+}
+
+fn read_body_or_default<T: DeserializeOwned + Default>(
+  request: &mut Request,
+) -> Result<T, ApiError> {
+  if request.body_length() == Some(0) {
+    return Ok(T::default());
+  }
+  read_body(request)
+}
+
+fn query_param(query: &str, name: &str) -> Option<String> {
+  query
+    .split('&')
+    .filter_map(|pair| pair.split_once('='))
+    .find(|(key, _)| *key == name)
+    .map(|(_, value)| percent_decode(value))
+}
+
+fn percent_decode(value: &str) -> String {
+  let bytes = value.as_bytes();
+  let mut decoded = Vec::with_capacity(bytes.len());
+  let mut index = 0;
+  while index < bytes.len() {
+    match bytes[index] {
+      b'%' if index + 2 < bytes.len() => {
+        let hex = std::str::from_utf8(&bytes[index + 1..index + 3]).unwrap_or("");
+        match u8::from_str_radix(hex, 16) {
+          Ok(byte) => {
+            decoded.push(byte);
+            index += 3;
+            continue;
+          }
+          Err(_) => decoded.push(b'%'),
+        }
+      }
+      b'+' => decoded.push(b' '),
+      byte => decoded.push(byte),
+    }
+    index += 1;
+  }
+  String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn json_response(status: u16, value: &Value) -> ApiResponse {
+  let payload = serde_json::to_vec(value).unwrap_or_default();
+  let content_type = Header::from_bytes("Content-Type", "application/json; charset=utf-8")
+    .expect("static header is valid");
+  Response::from_data(payload)
+    .with_status_code(status)
+    .with_header(content_type)
+}
+
+fn error_response(status: u16, message: String) -> ApiResponse {
+  json_response(status, &serde_json::json!({ "error": message }))
+}
+
+/// 命令错误按错误码映射状态码，响应体保留 `code`/`field`/`details` 供调用方区分。
+fn command_error_response(error: CommandError) -> ApiResponse {
+  let status = match error.code {
+    ErrorCode::NotFound => 404,
+    ErrorCode::Validation => 400,
+    _ => 500,
+  };
+  json_response(
+    status,
+    &serde_json::json!({
+      "error": error.message,
+      "code": error.code,
+      "field": error.field,
+      "details": error.details,
+    }),
+  )
+}
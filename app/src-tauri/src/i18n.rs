@@ -0,0 +1,325 @@
+use std::{
+  fmt,
+  sync::atomic::{AtomicU8, Ordering},
+};
+
+use crate::storage::Language;
+
+/// 当前界面语言；设置保存后由 [`set_language`] 更新，错误、通知与托盘文字随之切换。
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::Zh as u8);
+
+/// 按当前语言渲染文案，`{name}` 形式的占位符由具名参数替换。
+///
+/// ```ignore
+/// i18n::t!("tray.exit");
+/// i18n::t!("date.invalid", value = raw);
+/// ```
+macro_rules! t {
+  ($key:literal) => {
+    $crate::i18n::translate($key, &[])
+  };
+  ($key:literal, $($name:ident = $value:expr),+ $(,)?) => {
+    $crate::i18n::translate(
+      $key,
+      &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+    )
+  };
+}
+pub(crate) use t;
+
+pub fn current() -> Language {
+  match LANGUAGE.load(Ordering::Relaxed) {
+    value if value == Language::En as u8 => Language::En,
+    _ => Language::Zh,
+  }
+}
+
+pub fn set_language(language: Language) {
+  LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn translate(key: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+  let Some((zh, en)) = catalog(key) else {
+    log::warn!("缺少文案: {}", key);
+    return key.to_string();
+  };
+  let mut text = match current() {
+    Language::Zh => zh,
+    Language::En => en,
+  }
+  .to_string();
+  for (name, value) in args {
+    text = text.replace(&format!("{{{name}}}"), &value.to_string());
+  }
+  text
+}
+
+/// 文案表：`key => (中文, English)`。
+fn catalog(key: &str) -> Option<(&'static str, &'static str)> {
+  let entry = match key {
+    // 存储错误
+    "error.resolveDir" => (
+      "无法解析应用数据目录",
+      "Unable to resolve the app data directory",
+    ),
+    "error.io" => ("IO错误: {error}", "I/O error: {error}"),
+    "error.file" => (
+      "{operation}文件失败（{path}）: {error}",
+      "Failed to {operation} file ({path}): {error}",
+    ),
+    "error.json" => ("JSON 编解码失败: {error}", "JSON encoding failed: {error}"),
+    "error.corrupt" => (
+      "数据文件已损坏（{path}）: {error}",
+      "Data file is corrupted ({path}): {error}",
+    ),
+    "error.notFound" => ("数据不存在: {entity}", "Not found: {entity}"),
+    "error.validation" => ("数据校验失败: {message}", "Invalid data: {message}"),
+    "fileOperation.read" => ("读取", "read"),
+    "fileOperation.write" => ("写入", "write"),
+    "fileOperation.backup" => ("备份", "back up"),
+    "fileOperation.createDir" => ("创建目录", "create the directory for"),
+    "fileOperation.replace" => ("替换", "replace"),
+
+    // 通用校验
+    "date.invalid" => ("日期格式错误: {value}", "Invalid date: {value}"),
+    "date.outOfRange" => ("日期超出范围", "Date is out of range"),
+    "date.rangeReversed" => (
+      "开始日期不能晚于结束日期",
+      "Start date must not be after end date",
+    ),
+    "time.invalidStart" => ("开始时间格式错误", "Invalid start time"),
+    "time.invalidEnd" => ("结束时间格式错误", "Invalid end time"),
+
+    // 番茄钟与提醒
+    "pomodoro.nonPositive" => (
+      "番茄钟配置参数必须大于 0",
+      "Pomodoro durations and intervals must be greater than 0",
+    ),
+    "pomodoro.focusTooLong" => (
+      "专注时长不建议超过 180 分钟",
+      "Focus duration should not exceed 180 minutes",
+    ),
+    "pomodoro.focusDone" => ("专注阶段已完成", "Focus session complete"),
+    "pomodoro.focusDoneBody" => (
+      "恭喜完成一次专注，准备进入恢复阶段。",
+      "Nice work! Time to recover before the next round.",
+    ),
+    "pomodoro.shortBreakDone" => ("短休阶段已结束", "Short break is over"),
+    "pomodoro.shortBreakDoneBody" => (
+      "稍作休息，新的专注循环即将开始。",
+      "Rested a bit? The next focus round is about to begin.",
+    ),
+    "pomodoro.longBreakDone" => ("长休阶段已结束", "Long break is over"),
+    "pomodoro.longBreakDoneBody" => (
+      "长休结束，开启全新的专注循环吧。",
+      "Long break is done. Time to start a fresh cycle.",
+    ),
+    "reminder.offsetTooLarge" => (
+      "提醒提前量不能超过 30 天",
+      "Reminder offsets cannot exceed 30 days",
+    ),
+    "reminder.invalidSnooze" => (
+      "延后时长需在 1 分钟到 7 天之间",
+      "Snooze must be between 1 minute and 7 days",
+    ),
+    "reminder.untitled" => ("待办", "Todo"),
+    "reminder.summary" => ("{count} 条待办提醒", "{count} todo reminders"),
+    "reminder.line" => ("{title}：{detail}", "{title}: {detail}"),
+    "reminder.dueNow" => ("现在截止（{target}）", "Due now ({target})"),
+    "reminder.dueAt" => ("将于 {target} 截止", "Due at {target}"),
+    "reminder.plannedNow" => (
+      "现在计划开始（{target}）",
+      "Planned to start now ({target})",
+    ),
+    "reminder.plannedAt" => ("将于 {target} 计划开始", "Planned to start at {target}"),
+
+    "todo.patchNotObject" => (
+      "待办补丁必须是 JSON 对象",
+      "Todo patch must be a JSON object",
+    ),
+    "todo.invalid" => ("待办格式无效: {error}", "Invalid todo: {error}"),
+
+    "agenda.completedTodo" => (
+      "已完成的待办不能加入计划: {title}",
+      "Completed todos cannot be planned: {title}",
+    ),
+
+    "workflow.empty" => (
+      "工作流至少需要一个状态",
+      "The workflow needs at least one status",
+    ),
+    "workflow.duplicateStatus" => (
+      "工作流状态 id 为空或重复: {id}",
+      "Workflow status id is empty or duplicated: {id}",
+    ),
+    "workflow.unknownStatus" => ("未知的工作流状态: {id}", "Unknown workflow status: {id}"),
+    "workflow.initialDone" => (
+      "初始状态不能是完成状态",
+      "The initial status cannot be a done status",
+    ),
+    "workflow.noDoneStatus" => (
+      "工作流至少需要一个完成状态",
+      "The workflow needs at least one done status",
+    ),
+    "workflow.sameStatus" => ("待办已处于“{status}”状态", "The todo is already {status}"),
+    "workflow.transitionNotAllowed" => (
+      "不允许从“{from}”切换到“{to}”",
+      "Cannot move from {from} to {to}",
+    ),
+    "workflow.status.backlog" => ("待规划", "Backlog"),
+    "workflow.status.todo" => ("待办", "To do"),
+    "workflow.status.inProgress" => ("进行中", "In progress"),
+    "workflow.status.blocked" => ("受阻", "Blocked"),
+    "workflow.status.done" => ("已完成", "Done"),
+
+    "links.self" => ("待办不能关联自身", "A todo cannot be linked to itself"),
+    "links.duplicate" => (
+      "这两个待办已存在相同的关联",
+      "These todos are already linked this way",
+    ),
+    "links.cycle" => ("该关联会形成循环依赖", "This link would create a cycle"),
+    "links.unblockedTitle" => ("待办已解除阻塞", "Todo unblocked"),
+    "links.unblockedBody" => (
+      "“{blocker}”已完成，可以开始“{title}”",
+      "\"{blocker}\" is done, \"{title}\" is ready to start",
+    ),
+    "links.unblockedSummary" => ("{count} 个待办已解除阻塞", "{count} todos unblocked"),
+
+    // 排序
+    "ordering.otherList" => (
+      "相邻待办不存在或不在同一列表中",
+      "The neighbouring todo is missing or in another list",
+    ),
+    "ordering.invalidNeighbors" => (
+      "前一个待办必须排在后一个待办之前",
+      "The previous todo must come before the next one",
+    ),
+    "ordering.keyOrder" => (
+      "排序键 {low} 必须小于 {high}",
+      "Sort key {low} must come before {high}",
+    ),
+    "ordering.invalidKey" => ("排序键格式错误: {key}", "Invalid sort key: {key}"),
+
+    // 设置
+    "settings.patchNotObject" => (
+      "设置补丁必须是 JSON 对象",
+      "Settings patch must be a JSON object",
+    ),
+    "settings.invalid" => ("设置格式无效: {error}", "Invalid settings: {error}"),
+    "settings.invalidPort" => (
+      "本地 API 端口需在 {min} 到 65535 之间",
+      "Local API port must be between {min} and 65535",
+    ),
+    "settings.invalidFeedInterval" => (
+      "日历订阅的刷新间隔必须大于 0",
+      "Calendar feed refresh interval must be greater than 0",
+    ),
+    "hotkey.invalid" => (
+      "快捷键格式无效（{field}）: {accelerator}，{error}",
+      "Invalid shortcut ({field}): {accelerator}, {error}",
+    ),
+    "hotkey.conflict" => (
+      "快捷键冲突: {accelerator} 同时绑定到 {first} 和 {second}",
+      "Shortcut conflict: {accelerator} is bound to both {first} and {second}",
+    ),
+    "hotkey.registerFailed" => (
+      "快捷键 {shortcut} 注册失败（{field}），可能已被其他程序占用: {error}",
+      "Failed to register shortcut {shortcut} ({field}); it may be used by another app: {error}",
+    ),
+    "autostart.failed" => (
+      "无法更新开机启动项: {error}",
+      "Unable to update the login item: {error}",
+    ),
+    "diagnostics.zipFailed" => (
+      "打包诊断信息失败: {error}",
+      "Failed to package diagnostics: {error}",
+    ),
+
+    // 窗口
+    "window.unknownLabel" => ("未知窗口标签: {label}", "Unknown window label: {label}"),
+    "window.projectRequired" => (
+      "打开项目悬浮窗需要指定项目",
+      "A project is required to open a project window",
+    ),
+    "window.invalidOpacity" => (
+      "悬浮窗透明度需在 {min} 到 {max} 之间",
+      "Floating window opacity must be between {min} and {max}",
+    ),
+    "window.invalidSnapThreshold" => (
+      "吸附距离不能超过 {max} 像素",
+      "Snap distance cannot exceed {max} pixels",
+    ),
+    "window.quickAdd" => ("快速新建待办", "Quick Add"),
+    "window.statistics" => ("专注统计", "Focus Statistics"),
+
+    // 日历
+    "calendar.feedFolderRequired" => (
+      "请先选择日历订阅文件所在目录",
+      "Choose a folder for the calendar feed first",
+    ),
+    "calendar.feedFolderMissing" => (
+      "日历订阅目录不存在: {path}",
+      "Calendar feed folder does not exist: {path}",
+    ),
+    "calendar.fileMissing" => (
+      "日历文件不存在: {path}",
+      "Calendar file does not exist: {path}",
+    ),
+    "calendar.untitled" => ("(无标题)", "(Untitled)"),
+    "calendar.sessionSummary" => ("{phase}: {title}", "{phase}: {title}"),
+
+    // 工时表
+    "timesheet.title" => ("工时表 {from} ~ {to}", "Timesheet {from} ~ {to}"),
+    "timesheet.unassigned" => ("(未关联)", "(Unassigned)"),
+    "timesheet.date" => ("日期", "Date"),
+    "timesheet.groupTodo" => ("待办", "Todo"),
+    "timesheet.groupTag" => ("标签", "Tag"),
+    "timesheet.groupProject" => ("项目", "Project"),
+    "timesheet.sessions" => ("番茄数", "Pomodoros"),
+    "timesheet.trackedMinutes" => ("实际分钟", "Tracked minutes"),
+    "timesheet.billedMinutes" => ("计费分钟", "Billed minutes"),
+    "timesheet.billedHours" => ("计费小时", "Billed hours"),
+    "timesheet.total" => ("合计", "Total"),
+
+    // 本地 HTTP API
+    "httpApi.tokenRequired" => (
+      "启用 HTTP API 前必须设置访问令牌",
+      "An access token is required to enable the HTTP API",
+    ),
+    "httpApi.bindFailed" => (
+      "HTTP API 无法监听端口 {port}: {error}",
+      "HTTP API cannot listen on port {port}: {error}",
+    ),
+    "httpApi.unauthorized" => ("缺少或错误的访问令牌", "Missing or invalid access token"),
+    "httpApi.unknownRoute" => ("未知接口: {path}", "Unknown endpoint: {path}"),
+    "httpApi.methodNotAllowed" => ("不支持的请求方法: {method}", "Method not allowed: {method}"),
+    "httpApi.invalidBody" => ("请求体格式错误: {error}", "Invalid request body: {error}"),
+
+    // 托盘
+    "phase.focus" => ("专注", "Focus"),
+    "phase.shortBreak" => ("短休息", "Short Break"),
+    "phase.longBreak" => ("长休息", "Long Break"),
+    "tray.statusRunning" => ("{phase} · {clock}", "{phase} · {clock}"),
+    "tray.statusPaused" => ("{phase} · {clock}（已暂停）", "{phase} · {clock} (paused)"),
+    "tray.statusIdle" => ("{phase} · 就绪（{clock}）", "{phase} · ready ({clock})"),
+    "tray.task" => ("任务: {title}", "Task: {title}"),
+    "tray.taskNone" => ("无", "none"),
+    "tray.start" => ("开始{phase}", "Start {phase}"),
+    "tray.pause" => ("暂停", "Pause"),
+    "tray.resume" => ("继续", "Resume"),
+    "tray.skipPhase" => ("跳过当前阶段", "Skip Phase"),
+    "tray.resetTimer" => ("重置计时", "Reset Timer"),
+    "tray.mainWindow" => ("主窗口", "Main Window"),
+    "tray.floatingWindow" => ("悬浮窗", "Floating Window"),
+    "tray.showWindow" => ("显示{name}", "Show {name}"),
+    "tray.hideWindow" => ("隐藏{name}", "Hide {name}"),
+    "tray.todos" => ("待办", "Todos"),
+    "tray.noOpenTodos" => ("没有未完成的待办", "No open todos"),
+    "tray.startFocus" => ("开始专注", "Start Focus"),
+    "tray.markComplete" => ("标记完成", "Mark Complete"),
+    "tray.todoDue" => ("{title} · {due} 截止", "{title} · due {due}"),
+    "tray.exit" => ("退出", "Exit"),
+    _ => return None,
+  };
+  Some(entry)
+}
@@ -1,62 +1,147 @@
 use tauri::Manager;
 
+mod agenda;
+mod autostart;
+mod calendar;
 mod commands;
+mod diagnostics;
+mod hotkeys;
+mod http_api;
+mod i18n;
+mod links;
+mod logging;
+mod ordering;
+mod reminders;
 mod state;
 mod storage;
+mod timer;
+mod timesheet;
 mod windows;
+mod workflow;
 mod tray;
+mod tray_icon;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  println!("=== Tauri 应用启动 ===");
+  logging::install_panic_hook();
 
   tauri::Builder::default()
     .setup(|app| {
-      println!("=== Setup 开始 ===");
-
-      if cfg!(debug_assertions) {
-        println!("调试模式: 启用日志插件");
-        app.handle().plugin(
-          tauri_plugin_log::Builder::default()
-            .level(log::LevelFilter::Info)
-            .build(),
-        )?;
-      }
+      app.handle().plugin(logging::plugin(app.handle())?)?;
+      log::info!("FocusTodo {} 启动", app.package_info().version);
 
-      println!("加载通知插件...");
+      log::debug!("加载通知插件");
       app
         .handle()
         .plugin(tauri_plugin_notification::init())?;
 
-      println!("初始化存储...");
-      let store = storage::FileStore::initialize(&app.handle())?;
+      log::debug!("加载全局快捷键插件");
+      app.handle().plugin(
+        tauri_plugin_global_shortcut::Builder::new()
+          .with_handler(hotkeys::handle)
+          .build(),
+      )?;
+
+      log::debug!("加载开机启动插件");
+      app.handle().plugin(
+        tauri_plugin_autostart::Builder::new()
+          .arg(autostart::AUTOSTART_ARG)
+          .build(),
+      )?;
+
+      log::debug!("初始化存储");
+      let store = storage::FileStore::initialize(app.handle())?;
       app.manage(state::AppState::new(store));
+      timer::spawn_ticker(app.handle().clone());
+      calendar::feed::spawn(app.handle().clone());
+      calendar::import::spawn_watcher(app.handle().clone());
+      reminders::spawn(app.handle().clone());
+
+      log::debug!("启动本地 HTTP API");
+      let state = app.state::<state::AppState>();
+      let settings = state.store().load_settings().unwrap_or_default();
+      logging::set_level(settings.log_level);
+      i18n::set_language(settings.language);
+      if let Err(err) = state.http_api().apply(app.handle(), &settings.http_api) {
+        log::warn!("本地 HTTP API 启动失败: {}", err);
+      }
+
+      log::debug!("注册全局快捷键");
+      if let Err(err) = state.hotkeys().apply(app.handle(), &settings.hotkeys) {
+        log::warn!("全局快捷键注册失败: {}", err);
+      }
+
+      log::debug!("核对开机启动项");
+      if let Err(err) = autostart::verify(app.handle()) {
+        log::warn!("开机启动项核对失败: {}", err);
+      }
 
-      println!("初始化窗口...");
+      log::debug!("初始化窗口");
       windows::init(app)?;
-      println!("===  系统托盘初始化 ===");
-      tray::init(&app.handle())?;
-      println!("===  系统托盘完成 ===");
+      log::debug!("初始化系统托盘");
+      tray::init(app.handle())?;
 
-      println!("=== Setup 完成 ===");
+      log::info!("启动完成");
       Ok(())
     })
     .invoke_handler(tauri::generate_handler![
       commands::todo::list_todos,
       commands::todo::create_todo,
       commands::todo::update_todo,
+      commands::todo::patch_todo,
       commands::todo::delete_todo,
       commands::todo::toggle_complete,
+      commands::todo::transition_todo,
+      commands::todo::list_status_transitions,
+      commands::todo::move_todo,
+      commands::links::link_todos,
+      commands::links::unlink_todos,
+      commands::links::list_todo_links,
+      commands::links::list_ready_todos,
       commands::pomodoro::get_pomodoro_config,
       commands::pomodoro::save_pomodoro_config,
       commands::pomodoro::append_pomodoro_session,
       commands::pomodoro::list_pomodoro_sessions,
       commands::settings::get_settings,
       commands::settings::save_settings,
-      commands::settings::record_window_state
+      commands::settings::patch_settings,
+      commands::settings::record_window_state,
+      commands::settings::regenerate_http_api_token,
+      commands::diagnostics::export_diagnostics,
+      commands::timer::get_timer_state,
+      commands::timer::start_timer,
+      commands::timer::pause_timer,
+      commands::timer::toggle_timer,
+      commands::timer::reset_timer,
+      commands::timer::skip_phase,
+      commands::stats::get_focus_stats,
+      commands::calendar::export_ics,
+      commands::calendar::refresh_ics_feed,
+      commands::calendar::import_ics_file,
+      commands::calendar::list_calendar_sources,
+      commands::calendar::remove_calendar_source,
+      commands::calendar::list_calendar_events,
+      commands::calendar::get_day_plan,
+      commands::agenda::get_agenda,
+      commands::agenda::plan_day,
+      commands::timesheet::export_timesheet,
+      commands::reminders::list_reminders,
+      commands::reminders::snooze_reminder,
+      commands::reminders::dismiss_reminder,
+      commands::reminders::set_todo_reminders,
+      commands::window::toggle_window_visibility,
+      commands::window::show_window,
+      commands::window::hide_window,
+      commands::window::set_window_position,
+      commands::window::open_window,
+      commands::window::focus_window,
+      commands::window::close_window,
+      commands::window::set_window_preference,
+      commands::window::set_floating_mode,
+      commands::window::toggle_floating_mode
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 
-  println!("=== Tauri 应用退出 ===");
+  log::info!("FocusTodo 退出");
 }
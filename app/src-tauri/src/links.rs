@@ -0,0 +1,351 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_notification::NotificationExt;
+use uuid::Uuid;
+
+use crate::{
+  i18n,
+  state::AppState,
+  storage::{LinkKind, StorageError, TodoItem, TodoLink},
+};
+
+/// 阻塞项全部完成后广播，载荷为解除阻塞的待办 id。
+pub const UNBLOCKED_EVENT: &str = "todos:unblocked";
+
+/// 一次解除阻塞超过该数量时合并为一条通知。
+const MAX_INDIVIDUAL_NOTIFICATIONS: usize = 3;
+
+/// 校验并创建关联：两端必须是不同的已有待办，不能重复，`blocks`/`duplicateOf` 不能形成环。
+pub fn add(
+  todos: &[TodoItem],
+  links: &mut Vec<TodoLink>,
+  kind: LinkKind,
+  from: &str,
+  to: &str,
+) -> Result<TodoLink, StorageError> {
+  let (kind, from, to) = match kind {
+    LinkKind::BlockedBy => (LinkKind::Blocks, to, from),
+    kind => (kind, from, to),
+  };
+  if from == to {
+    return Err(StorageError::invalid_field("to", i18n::t!("links.self")));
+  }
+  for id in [from, to] {
+    if !todos.iter().any(|todo| todo.id == id) {
+      return Err(StorageError::NotFound("todo"));
+    }
+  }
+  let exists = links.iter().any(|link| {
+    link.kind == kind
+      && ((link.from == from && link.to == to)
+        || (kind == LinkKind::Related && link.from == to && link.to == from))
+  });
+  if exists {
+    return Err(StorageError::validation(i18n::t!("links.duplicate")));
+  }
+  if kind != LinkKind::Related && reaches(links, kind, to, from) {
+    return Err(StorageError::validation(i18n::t!("links.cycle")));
+  }
+
+  let link = TodoLink {
+    id: Uuid::new_v4().to_string(),
+    kind,
+    from: from.to_string(),
+    to: to.to_string(),
+    created_at: Utc::now().to_rfc3339(),
+  };
+  links.push(link.clone());
+  Ok(link)
+}
+
+/// 删除待办时一并删除与它相关的关联，返回是否有变化。
+pub fn remove_todo(links: &mut Vec<TodoLink>, todo_id: &str) -> bool {
+  let initial_len = links.len();
+  links.retain(|link| link.from != todo_id && link.to != todo_id);
+  links.len() != initial_len
+}
+
+/// 未完成且所有阻塞项都已完成（或已删除）的待办。
+pub fn ready<'a>(todos: &'a [TodoItem], links: &[TodoLink]) -> Vec<&'a TodoItem> {
+  let completed = completion(todos);
+  todos
+    .iter()
+    .filter(|todo| !todo.completed)
+    .filter(|todo| blockers(links, &todo.id).all(|id| is_done(&completed, id)))
+    .collect()
+}
+
+/// `blocker_id` 完成后变为可开始的待办：被它阻塞、未完成，且其余阻塞项也都已完成。
+pub fn unblocked_by<'a>(
+  todos: &'a [TodoItem],
+  links: &[TodoLink],
+  blocker_id: &str,
+) -> Vec<&'a TodoItem> {
+  let completed = completion(todos);
+  links
+    .iter()
+    .filter(|link| link.kind == LinkKind::Blocks && link.from == blocker_id)
+    .filter_map(|link| todos.iter().find(|todo| todo.id == link.to))
+    .filter(|todo| !todo.completed)
+    .filter(|todo| blockers(links, &todo.id).all(|id| is_done(&completed, id)))
+    .collect()
+}
+
+/// 待办完成后通知因此解除阻塞的待办。
+pub fn notify_unblocked(app: &AppHandle, state: &AppState, blocker: &TodoItem) {
+  let (todos, links) = match (state.store().load_todos(), state.store().load_links()) {
+    (Ok(todos), Ok(links)) => (todos, links),
+    (Err(err), _) | (_, Err(err)) => {
+      log::warn!("读取待办关联失败: {}", err);
+      return;
+    }
+  };
+  let unblocked = unblocked_by(&todos, &links, &blocker.id);
+  if unblocked.is_empty() {
+    return;
+  }
+  log::debug!("待办 {} 完成后解除阻塞 {} 项", blocker.id, unblocked.len());
+
+  if unblocked.len() > MAX_INDIVIDUAL_NOTIFICATIONS {
+    let body = unblocked
+      .iter()
+      .map(|todo| todo.title.as_str())
+      .collect::<Vec<_>>()
+      .join("\n");
+    show(
+      app,
+      &i18n::t!("links.unblockedSummary", count = unblocked.len()),
+      &body,
+    );
+  } else {
+    for todo in &unblocked {
+      show(
+        app,
+        &i18n::t!("links.unblockedTitle"),
+        &i18n::t!(
+          "links.unblockedBody",
+          blocker = blocker.title,
+          title = todo.title
+        ),
+      );
+    }
+  }
+  let ids: Vec<&str> = unblocked.iter().map(|todo| todo.id.as_str()).collect();
+  if let Err(err) = app.emit(UNBLOCKED_EVENT, ids) {
+    log::warn!("广播解除阻塞事件失败: {}", err);
+  }
+}
+
+fn show(app: &AppHandle, title: &str, body: &str) {
+  if let Err(err) = app.notification().builder().title(title).body(body).show() {
+    log::warn!("发送解除阻塞通知失败: {}", err);
+  }
+}
+
+fn blockers<'a>(links: &'a [TodoLink], todo_id: &'a str) -> impl Iterator<Item = &'a str> {
+  links
+    .iter()
+    .filter(move |link| link.kind == LinkKind::Blocks && link.to == todo_id)
+    .map(|link| link.from.as_str())
+}
+
+fn completion(todos: &[TodoItem]) -> HashMap<&str, bool> {
+  todos
+    .iter()
+    .map(|todo| (todo.id.as_str(), todo.completed))
+    .collect()
+}
+
+/// 已删除的阻塞项视为已完成。
+fn is_done(completed: &HashMap<&str, bool>, id: &str) -> bool {
+  completed.get(id).copied().unwrap_or(true)
+}
+
+/// 沿 `kind` 类型的关联能否从 `start` 走到 `goal`。
+fn reaches(links: &[TodoLink], kind: LinkKind, start: &str, goal: &str) -> bool {
+  let mut visited = HashSet::new();
+  let mut stack = vec![start];
+  while let Some(current) = stack.pop() {
+    if current == goal {
+      return true;
+    }
+    if !visited.insert(current) {
+      continue;
+    }
+    stack.extend(
+      links
+        .iter()
+        .filter(|link| link.kind == kind && link.from == current)
+        .map(|link| link.to.as_str()),
+    );
+  }
+  false
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn todo(id: &str, completed: bool) -> TodoItem {
+    serde_json::from_value(json!({
+      "id": id,
+      "title": id.to_uppercase(),
+      "completed": completed,
+      "createdAt": "2026-03-01T00:00:00Z",
+      "updatedAt": "2026-03-01T00:00:00Z",
+    }))
+    .unwrap()
+  }
+
+  fn todos(completed: &[&str]) -> Vec<TodoItem> {
+    ["a", "b", "c", "d"]
+      .into_iter()
+      .map(|id| todo(id, completed.contains(&id)))
+      .collect()
+  }
+
+  fn is_validation(result: Result<TodoLink, StorageError>) -> bool {
+    matches!(result, Err(StorageError::Validation { .. }))
+  }
+
+  fn ids(todos: Vec<&TodoItem>) -> Vec<&str> {
+    todos.into_iter().map(|todo| todo.id.as_str()).collect()
+  }
+
+  #[test]
+  fn blocked_by_is_stored_as_reversed_blocks() {
+    let todos = todos(&[]);
+    let mut links = Vec::new();
+    let link = add(&todos, &mut links, LinkKind::BlockedBy, "a", "b").unwrap();
+    assert_eq!(
+      (link.kind, link.from.as_str(), link.to.as_str()),
+      (LinkKind::Blocks, "b", "a")
+    );
+    // 反方向再建一次 `blocks` 即为重复。
+    assert!(is_validation(add(
+      &todos,
+      &mut links,
+      LinkKind::Blocks,
+      "b",
+      "a"
+    )));
+    assert_eq!(links.len(), 1);
+  }
+
+  #[test]
+  fn rejects_self_links_and_missing_todos() {
+    let todos = todos(&[]);
+    let mut links = Vec::new();
+    for kind in [LinkKind::Blocks, LinkKind::Related, LinkKind::DuplicateOf] {
+      assert!(is_validation(add(&todos, &mut links, kind, "a", "a")));
+    }
+    assert!(matches!(
+      add(&todos, &mut links, LinkKind::Blocks, "a", "zz"),
+      Err(StorageError::NotFound("todo"))
+    ));
+    assert!(links.is_empty());
+  }
+
+  #[test]
+  fn rejects_duplicates() {
+    let todos = todos(&[]);
+    let mut links = Vec::new();
+    add(&todos, &mut links, LinkKind::Blocks, "a", "b").unwrap();
+    add(&todos, &mut links, LinkKind::Related, "a", "c").unwrap();
+    assert!(is_validation(add(
+      &todos,
+      &mut links,
+      LinkKind::Blocks,
+      "a",
+      "b"
+    )));
+    // `related` 不分方向。
+    assert!(is_validation(add(
+      &todos,
+      &mut links,
+      LinkKind::Related,
+      "c",
+      "a"
+    )));
+    // 不同类型的关联可以并存。
+    add(&todos, &mut links, LinkKind::Related, "a", "b").unwrap();
+    assert_eq!(links.len(), 3);
+  }
+
+  #[test]
+  fn rejects_direct_and_transitive_cycles() {
+    let todos = todos(&[]);
+    for kind in [LinkKind::Blocks, LinkKind::DuplicateOf] {
+      let mut links = Vec::new();
+      add(&todos, &mut links, kind, "a", "b").unwrap();
+      assert!(
+        is_validation(add(&todos, &mut links, kind, "b", "a")),
+        "{kind:?}"
+      );
+
+      add(&todos, &mut links, kind, "b", "c").unwrap();
+      add(&todos, &mut links, kind, "c", "d").unwrap();
+      assert!(
+        is_validation(add(&todos, &mut links, kind, "d", "a")),
+        "{kind:?}"
+      );
+      assert_eq!(links.len(), 3, "{kind:?}");
+    }
+  }
+
+  #[test]
+  fn cycles_are_checked_per_kind() {
+    let todos = todos(&[]);
+    let mut links = Vec::new();
+    add(&todos, &mut links, LinkKind::Blocks, "a", "b").unwrap();
+    add(&todos, &mut links, LinkKind::DuplicateOf, "b", "a").unwrap();
+    add(&todos, &mut links, LinkKind::Related, "a", "c").unwrap();
+    add(&todos, &mut links, LinkKind::Related, "c", "b").unwrap();
+    add(&todos, &mut links, LinkKind::Related, "b", "a").unwrap();
+    // 经 `blockedBy` 换向后 c → a 与 a → b → … 没有形成环。
+    add(&todos, &mut links, LinkKind::BlockedBy, "a", "c").unwrap();
+    assert!(is_validation(add(
+      &todos,
+      &mut links,
+      LinkKind::BlockedBy,
+      "c",
+      "b"
+    )));
+  }
+
+  #[test]
+  fn unblocked_only_after_last_blocker_completes() {
+    let mut links = Vec::new();
+    add(&todos(&[]), &mut links, LinkKind::Blocks, "a", "c").unwrap();
+    add(&todos(&[]), &mut links, LinkKind::Blocks, "b", "c").unwrap();
+    add(&todos(&[]), &mut links, LinkKind::Blocks, "a", "d").unwrap();
+
+    // a 完成时 c 仍被 b 阻塞，只有 d 解除阻塞。
+    assert_eq!(ids(unblocked_by(&todos(&["a"]), &links, "a")), ["d"]);
+    // b 随后完成，c 的最后一个阻塞项完成。
+    assert_eq!(ids(unblocked_by(&todos(&["a", "b"]), &links, "b")), ["c"]);
+    // 已完成的被阻塞项不再通知。
+    assert!(unblocked_by(&todos(&["a", "b", "c"]), &links, "b").is_empty());
+  }
+
+  #[test]
+  fn deleted_blockers_count_as_done() {
+    let mut links = Vec::new();
+    add(&todos(&[]), &mut links, LinkKind::Blocks, "a", "c").unwrap();
+    add(&todos(&[]), &mut links, LinkKind::Blocks, "b", "c").unwrap();
+    let remaining: Vec<TodoItem> = todos(&["a"])
+      .into_iter()
+      .filter(|todo| todo.id != "b")
+      .collect();
+    assert_eq!(ids(unblocked_by(&remaining, &links, "a")), ["c"]);
+    assert_eq!(ids(ready(&remaining, &links)), ["c", "d"]);
+
+    assert!(remove_todo(&mut links, "c"));
+    assert!(links.is_empty());
+    assert!(!remove_todo(&mut links, "c"));
+  }
+}
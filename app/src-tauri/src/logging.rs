@@ -0,0 +1,70 @@
+use std::{panic, path::PathBuf};
+
+use log::LevelFilter;
+use tauri::{plugin::TauriPlugin, AppHandle, Manager, Wry};
+use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+use crate::storage::{LogLevel, StorageError};
+
+pub const LOG_FILE_NAME: &str = "focustodo";
+/// 单个日志文件超过该大小后在下次启动时轮转。
+const MAX_LOG_FILE_BYTES: u128 = 2 * 1024 * 1024;
+const KEEP_LOG_FILES: usize = 5;
+
+/// 日志文件所在目录：应用数据目录下的 `logs`。
+pub fn log_dir(app: &AppHandle) -> Result<PathBuf, StorageError> {
+  app
+    .path()
+    .app_data_dir()
+    .map(|dir| dir.join("logs"))
+    .map_err(|_| StorageError::ResolveDir)
+}
+
+/// 日志插件：调试构建输出到终端与前端控制台，发布构建写入按大小轮转的日志文件。
+///
+/// 插件本身放行所有级别，实际输出级别由设置中的 `logLevel` 通过 [`set_level`] 控制。
+pub fn plugin(app: &AppHandle) -> Result<TauriPlugin<Wry>, StorageError> {
+  let targets = if cfg!(debug_assertions) {
+    vec![
+      Target::new(TargetKind::Stdout),
+      Target::new(TargetKind::Webview),
+    ]
+  } else {
+    vec![Target::new(TargetKind::Folder {
+      path: log_dir(app)?,
+      file_name: Some(LOG_FILE_NAME.to_string()),
+    })]
+  };
+  Ok(
+    tauri_plugin_log::Builder::new()
+      .clear_targets()
+      .targets(targets)
+      .level(LevelFilter::Trace)
+      // 窗口与 WebView 库的调试日志过多，最多保留到 info。
+      .level_for("tao", LevelFilter::Info)
+      .level_for("wry", LevelFilter::Info)
+      .rotation_strategy(RotationStrategy::KeepSome(KEEP_LOG_FILES))
+      .max_file_size(MAX_LOG_FILE_BYTES)
+      .timezone_strategy(TimezoneStrategy::UseLocal)
+      .build(),
+  )
+}
+
+pub fn set_level(level: LogLevel) {
+  log::set_max_level(match level {
+    LogLevel::Error => LevelFilter::Error,
+    LogLevel::Warn => LevelFilter::Warn,
+    LogLevel::Info => LevelFilter::Info,
+    LogLevel::Debug => LevelFilter::Debug,
+    LogLevel::Trace => LevelFilter::Trace,
+  });
+}
+
+/// 把 panic 信息也写入日志，发布构建没有终端时仍能在诊断包里看到。
+pub fn install_panic_hook() {
+  let previous = panic::take_hook();
+  panic::set_hook(Box::new(move |info| {
+    log::error!("程序崩溃: {}", info);
+    previous(info);
+  }));
+}
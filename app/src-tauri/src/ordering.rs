@@ -0,0 +1,490 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use chrono::Utc;
+
+use crate::{
+  i18n,
+  storage::{StorageError, TodoItem},
+};
+
+/// 手动排序后广播给所有窗口，载荷为被移动的待办。
+pub const REORDERED_EVENT: &str = "todos:reordered";
+
+/// 排序键使用的 base62 数字，按 ASCII 顺序排列，字符串比较即数值比较。
+const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+/// 整数部分为 0 的键，空列表的第一个键。
+const INTEGER_ZERO: &str = "a0";
+/// 最小的整数部分，它之前无法再插入整数键，单独出现时不是合法的键。
+const SMALLEST_INTEGER: &str = "A00000000000000000000000000";
+
+/// 排序范围：同一项目内的待办互相排序，未归属项目的待办属于同一个列表。
+pub fn scope(todo: &TodoItem) -> Option<&str> {
+  todo
+    .project
+    .as_deref()
+    .map(str::trim)
+    .filter(|project| !project.is_empty())
+}
+
+/// 列表顺序：有排序键的在前并按键排序，没有键的旧数据保持原有顺序排在后面。
+pub fn compare(a: &TodoItem, b: &TodoItem) -> Ordering {
+  match (&a.sort_key, &b.sort_key) {
+    (Some(a), Some(b)) => a.cmp(b),
+    (Some(_), None) => Ordering::Less,
+    (None, Some(_)) => Ordering::Greater,
+    (None, None) => Ordering::Equal,
+  }
+}
+
+/// 排在 `scope` 列表末尾的新键；格式错误的键不参与比较，下次移动时会被重新生成。
+pub fn append_key(todos: &[TodoItem], scope: Option<&str>) -> String {
+  key_after(last_valid_key(todos, scope))
+}
+
+/// 把待办移动到同一列表中 `before` 之后、`after` 之前；只给出一侧时紧挨该待办，都不给时移到末尾。
+///
+/// 只改写被移动待办的排序键；列表中还没有排序键的旧数据按原有顺序补上键，
+/// 并发新建产生重复键或键被手动改坏时按当前顺序重新生成整个列表的键。
+pub fn move_todo(
+  todos: &mut [TodoItem],
+  id: &str,
+  before: Option<&str>,
+  after: Option<&str>,
+) -> Result<TodoItem, StorageError> {
+  let index = todos
+    .iter()
+    .position(|todo| todo.id == id)
+    .ok_or(StorageError::NotFound("todo"))?;
+  let list_scope = scope(&todos[index]).map(str::to_string);
+  backfill(todos, list_scope.as_deref());
+  if needs_rekey(todos, list_scope.as_deref()) {
+    rekey(todos, list_scope.as_deref());
+  }
+
+  let mut keys: Vec<(&str, &str)> = todos
+    .iter()
+    .filter(|todo| todo.id != id && scope(todo) == list_scope.as_deref())
+    .filter_map(|todo| Some((todo.sort_key.as_deref()?, todo.id.as_str())))
+    .collect();
+  keys.sort();
+  let neighbor = |field: &str, neighbor_id: &str| {
+    keys
+      .iter()
+      .position(|(_, id)| *id == neighbor_id)
+      .ok_or_else(|| StorageError::invalid_field(field, i18n::t!("ordering.otherList")))
+  };
+  let (low, high) = match (before, after) {
+    (Some(before), Some(after)) => {
+      let (low, high) = (neighbor("before", before)?, neighbor("after", after)?);
+      if low >= high {
+        return Err(StorageError::invalid_field(
+          "after",
+          i18n::t!("ordering.invalidNeighbors"),
+        ));
+      }
+      (Some(keys[low].0), Some(keys[high].0))
+    }
+    (Some(before), None) => {
+      let low = neighbor("before", before)?;
+      (Some(keys[low].0), keys.get(low + 1).map(|(key, _)| *key))
+    }
+    (None, Some(after)) => {
+      let high = neighbor("after", after)?;
+      let low = high.checked_sub(1).map(|low| keys[low].0);
+      (low, Some(keys[high].0))
+    }
+    (None, None) => (keys.last().map(|(key, _)| *key), None),
+  };
+  let key = key_between(low, high)?;
+
+  let todo = &mut todos[index];
+  todo.sort_key = Some(key);
+  todo.updated_at = Utc::now().to_rfc3339();
+  Ok(todo.clone())
+}
+
+/// 给列表中还没有排序键的待办按存储顺序追加键。
+fn backfill(todos: &mut [TodoItem], list_scope: Option<&str>) {
+  let mut last = last_valid_key(todos, list_scope).map(str::to_string);
+  for todo in todos
+    .iter_mut()
+    .filter(|todo| todo.sort_key.is_none() && scope(todo) == list_scope)
+  {
+    let key = key_after(last.as_deref());
+    todo.sort_key = Some(key.clone());
+    last = Some(key);
+  }
+}
+
+fn last_valid_key<'a>(todos: &'a [TodoItem], list_scope: Option<&str>) -> Option<&'a str> {
+  todos
+    .iter()
+    .filter(|todo| scope(todo) == list_scope)
+    .filter_map(|todo| todo.sort_key.as_deref())
+    .filter(|key| split(key).is_some())
+    .max()
+}
+
+/// 列表中有重复或格式错误的键时需要重新生成。
+fn needs_rekey(todos: &[TodoItem], list_scope: Option<&str>) -> bool {
+  let mut seen = HashSet::new();
+  todos
+    .iter()
+    .filter(|todo| scope(todo) == list_scope)
+    .filter_map(|todo| todo.sort_key.as_deref())
+    .any(|key| split(key).is_none() || !seen.insert(key))
+}
+
+/// 按当前顺序（键相同时按 id）给列表中的待办重新生成互不相同的键。
+fn rekey(todos: &mut [TodoItem], list_scope: Option<&str>) {
+  let mut order: Vec<usize> = (0..todos.len())
+    .filter(|index| scope(&todos[*index]) == list_scope)
+    .collect();
+  order.sort_by(|a, b| {
+    (&todos[*a].sort_key, &todos[*a].id).cmp(&(&todos[*b].sort_key, &todos[*b].id))
+  });
+  let mut last: Option<String> = None;
+  for index in order {
+    let key = key_after(last.as_deref());
+    todos[index].sort_key = Some(key.clone());
+    last = Some(key);
+  }
+}
+
+/// 分数索引：生成严格位于 `a` 与 `b` 之间的键，`None` 表示列表开头或末尾。
+///
+/// 键由整数部分与小数部分组成：整数部分的首字符决定它的长度，在末尾追加时只递增整数，
+/// 键长随待办数量对数增长；两个键之间没有空余整数时在小数部分取中点，不需要改写其他待办。
+/// 键格式错误或 `a` 不小于 `b` 时返回校验错误。
+pub fn key_between(a: Option<&str>, b: Option<&str>) -> Result<String, StorageError> {
+  let low = a.map(parse).transpose()?;
+  let high = b.map(parse).transpose()?;
+  if let (Some(a), Some(b)) = (a, b) {
+    if a >= b {
+      return Err(StorageError::validation(i18n::t!(
+        "ordering.keyOrder",
+        low = a,
+        high = b
+      )));
+    }
+  }
+  Ok(match (low, high) {
+    (None, None) => INTEGER_ZERO.to_string(),
+    (Some(low), None) => after(low),
+    (None, Some(high)) => before(high),
+    (Some((integer_a, fraction_a)), Some((integer_b, fraction_b))) => {
+      if integer_a == integer_b {
+        format!(
+          "{integer_a}{}",
+          encode(&midpoint(&fraction_a, Some(&fraction_b)))
+        )
+      } else {
+        match increment(integer_a) {
+          Some(next) if b.is_some_and(|b| next.as_str() < b) => next,
+          _ => format!("{integer_a}{}", encode(&midpoint(&fraction_a, None))),
+        }
+      }
+    }
+  })
+}
+
+/// 排在合法键 `a` 之后的键，`None` 表示空列表。
+fn key_after(a: Option<&str>) -> String {
+  a.and_then(split)
+    .map_or_else(|| INTEGER_ZERO.to_string(), after)
+}
+
+fn after((integer, fraction): (&str, Vec<usize>)) -> String {
+  increment(integer).unwrap_or_else(|| format!("{integer}{}", encode(&midpoint(&fraction, None))))
+}
+
+fn before((integer, fraction): (&str, Vec<usize>)) -> String {
+  if integer == SMALLEST_INTEGER {
+    return format!("{integer}{}", encode(&midpoint(&[], Some(&fraction))));
+  }
+  if !fraction.is_empty() {
+    return integer.to_string();
+  }
+  match decrement(integer) {
+    Some(previous) if previous != SMALLEST_INTEGER => previous,
+    _ => format!("{SMALLEST_INTEGER}{}", encode(&midpoint(&[], None))),
+  }
+}
+
+fn parse(key: &str) -> Result<(&str, Vec<usize>), StorageError> {
+  split(key).ok_or_else(|| StorageError::validation(i18n::t!("ordering.invalidKey", key = key)))
+}
+
+/// 拆出整数部分与小数部分的数值；含非 base62 字符、整数部分不完整或小数部分以 `0` 结尾时返回 `None`。
+fn split(key: &str) -> Option<(&str, Vec<usize>)> {
+  let length = integer_length(*key.as_bytes().first()?)?;
+  let digits = values(key.get(1..)?)?;
+  if key.len() < length || key == SMALLEST_INTEGER {
+    return None;
+  }
+  let fraction = digits[length - 1..].to_vec();
+  if fraction.last() == Some(&0) {
+    return None;
+  }
+  Some((&key[..length], fraction))
+}
+
+/// 整数部分的总长度：`a`..`z` 为非负整数，`A`..`Z` 为负整数，离 `a`/`Z` 越远位数越多。
+fn integer_length(head: u8) -> Option<usize> {
+  match head {
+    b'a'..=b'z' => Some(usize::from(head - b'a') + 2),
+    b'A'..=b'Z' => Some(usize::from(b'Z' - head) + 2),
+    _ => None,
+  }
+}
+
+/// 整数部分加一；已是最大的整数时返回 `None`。
+fn increment(integer: &str) -> Option<String> {
+  let head = integer.as_bytes()[0];
+  let mut digits = values(&integer[1..])?;
+  for digit in digits.iter_mut().rev() {
+    if *digit + 1 < DIGITS.len() {
+      *digit += 1;
+      return Some(format!("{}{}", char::from(head), encode(&digits)));
+    }
+    *digit = 0;
+  }
+  // 所有位都进位时换一个首字符：非负整数多一位，负整数少一位。
+  let head = match head {
+    b'Z' => return Some(INTEGER_ZERO.to_string()),
+    b'z' => return None,
+    head => head + 1,
+  };
+  if head > b'a' {
+    digits.push(0);
+  } else {
+    digits.pop();
+  }
+  Some(format!("{}{}", char::from(head), encode(&digits)))
+}
+
+/// 整数部分减一；已是最小的整数时返回 `None`。
+fn decrement(integer: &str) -> Option<String> {
+  let head = integer.as_bytes()[0];
+  let mut digits = values(&integer[1..])?;
+  let max = DIGITS.len() - 1;
+  for digit in digits.iter_mut().rev() {
+    if *digit > 0 {
+      *digit -= 1;
+      return Some(format!("{}{}", char::from(head), encode(&digits)));
+    }
+    *digit = max;
+  }
+  let head = match head {
+    b'a' => return Some(format!("Z{}", char::from(DIGITS[max]))),
+    b'A' => return None,
+    head => head - 1,
+  };
+  if head < b'Z' {
+    digits.push(max);
+  } else {
+    digits.pop();
+  }
+  Some(format!("{}{}", char::from(head), encode(&digits)))
+}
+
+/// 小数部分 `a` 与 `b` 的中点，`None` 表示 1；调用方保证 `a < b` 且都不以 `0` 结尾。
+fn midpoint(a: &[usize], b: Option<&[usize]>) -> Vec<usize> {
+  if let Some(b) = b {
+    let common = b
+      .iter()
+      .enumerate()
+      .take_while(|(index, digit)| a.get(*index).copied().unwrap_or(0) == **digit)
+      .count();
+    if common > 0 {
+      let mut key = b[..common].to_vec();
+      key.extend(midpoint(
+        a.get(common..).unwrap_or_default(),
+        Some(&b[common..]),
+      ));
+      return key;
+    }
+  }
+  let digit_a = a.first().copied().unwrap_or(0);
+  let digit_b = b.and_then(|b| b.first().copied()).unwrap_or(DIGITS.len());
+  if digit_b > digit_a + 1 {
+    return vec![(digit_a + digit_b).div_ceil(2)];
+  }
+  match b {
+    Some(b) if b.len() > 1 => vec![b[0]],
+    _ => {
+      let mut key = vec![digit_a];
+      key.extend(midpoint(a.get(1..).unwrap_or_default(), None));
+      key
+    }
+  }
+}
+
+/// 每个字符的 base62 数值，含其他字符时返回 `None`。
+fn values(digits: &str) -> Option<Vec<usize>> {
+  digits
+    .bytes()
+    .map(|digit| DIGITS.iter().position(|candidate| *candidate == digit))
+    .collect()
+}
+
+fn encode(values: &[usize]) -> String {
+  values
+    .iter()
+    .map(|value| char::from(DIGITS[*value]))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn between(a: Option<&str>, b: Option<&str>) -> String {
+    let key = key_between(a, b).unwrap();
+    assert!(a.is_none_or(|a| a < key.as_str()), "{a:?} < {key}");
+    assert!(b.is_none_or(|b| key.as_str() < b), "{key} < {b:?}");
+    key
+  }
+
+  fn todo(id: &str, sort_key: Option<&str>) -> TodoItem {
+    serde_json::from_value(serde_json::json!({
+      "id": id,
+      "title": id,
+      "sortKey": sort_key,
+      "createdAt": "2026-01-01T00:00:00Z",
+      "updatedAt": "2026-01-01T00:00:00Z",
+    }))
+    .unwrap()
+  }
+
+  fn keys(todos: &[TodoItem]) -> Vec<&str> {
+    todos
+      .iter()
+      .map(|todo| todo.sort_key.as_deref().unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn first_key_is_integer_zero() {
+    assert_eq!(between(None, None), "a0");
+  }
+
+  #[test]
+  fn inserts_before_the_first_key() {
+    assert_eq!(between(None, Some("a0")), "Zz");
+    assert_eq!(between(None, Some("a0V")), "a0");
+  }
+
+  #[test]
+  fn appends_increment_the_integer() {
+    assert_eq!(between(Some("a0"), None), "a1");
+    assert_eq!(between(Some("az"), None), "b00");
+    assert_eq!(between(Some("Zz"), None), "a0");
+    assert_eq!(between(Some("a0V"), None), "a1");
+  }
+
+  #[test]
+  fn inserts_between_adjacent_integers() {
+    assert_eq!(between(Some("a1"), Some("a2")), "a1V");
+    assert_eq!(between(Some("a0"), Some("a3")), "a1");
+  }
+
+  #[test]
+  fn keeps_the_common_prefix() {
+    assert_eq!(between(Some("a1V"), Some("a1W")), "a1VV");
+    assert_eq!(between(Some("a1a1"), Some("a1a3")), "a1a2");
+  }
+
+  #[test]
+  fn repeated_inserts_stay_ordered() {
+    let low = "a1".to_string();
+    let mut high = "a2".to_string();
+    for _ in 0..100 {
+      high = between(Some(&low), Some(&high));
+    }
+    let mut low = "a1".to_string();
+    let high = "a2".to_string();
+    for _ in 0..100 {
+      low = between(Some(&low), Some(&high));
+    }
+  }
+
+  #[test]
+  fn append_and_prepend_keys_grow_logarithmically() {
+    let mut last = between(None, None);
+    for _ in 0..10_000 {
+      last = between(Some(&last), None);
+    }
+    assert_eq!(last.len(), 4, "{last}");
+
+    let mut first = between(None, None);
+    for _ in 0..10_000 {
+      first = between(None, Some(&first));
+    }
+    assert_eq!(first.len(), 4, "{first}");
+  }
+
+  #[test]
+  fn rejects_keys_out_of_order() {
+    assert!(key_between(Some("a2"), Some("a1")).is_err());
+    assert!(key_between(Some("a1"), Some("a1")).is_err());
+  }
+
+  #[test]
+  fn rejects_malformed_keys() {
+    for key in [
+      "",
+      "a",
+      "V",
+      "a1~",
+      "a1 ",
+      "a1中",
+      "a10",
+      "-1",
+      SMALLEST_INTEGER,
+    ] {
+      assert!(key_between(Some(key), None).is_err(), "{key:?}");
+      assert!(key_between(None, Some(key)).is_err(), "{key:?}");
+    }
+    // 曾经会在中点计算时下溢的组合。
+    assert!(key_between(Some("a1!"), Some("a1V")).is_err());
+    assert!(key_between(Some("a1"), Some("a1~")).is_err());
+  }
+
+  #[test]
+  fn append_ignores_malformed_keys() {
+    let todos = vec![todo("a", Some("a5")), todo("b", Some("~~"))];
+    assert_eq!(append_key(&todos, None), "a6");
+  }
+
+  #[test]
+  fn move_rekeys_duplicate_keys() {
+    let mut todos = vec![
+      todo("a", Some("a0")),
+      todo("b", Some("a0")),
+      todo("c", Some("a5")),
+    ];
+    let moved = move_todo(&mut todos, "c", Some("a"), Some("b")).unwrap();
+    let keys = keys(&todos);
+    assert!(keys[0] < keys[2] && keys[2] < keys[1], "{keys:?}");
+    assert_eq!(moved.sort_key.as_deref(), Some(keys[2]));
+  }
+
+  #[test]
+  fn move_rekeys_malformed_keys() {
+    let mut todos = vec![
+      todo("a", Some("a3")),
+      todo("b", Some("a1~")),
+      todo("c", Some("V")),
+      todo("d", None),
+    ];
+    move_todo(&mut todos, "d", None, Some("a")).unwrap();
+    let keys = keys(&todos);
+    assert!(keys.iter().all(|key| split(key).is_some()), "{keys:?}");
+    // 重新生成时保留原有顺序：V < a1~ < a3，d 移到 a 之前。
+    assert!(
+      keys[2] < keys[1] && keys[1] < keys[3] && keys[3] < keys[0],
+      "{keys:?}"
+    );
+  }
+}
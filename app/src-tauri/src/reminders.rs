@@ -0,0 +1,273 @@
+use std::{collections::HashMap, thread, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use parking_lot::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{
+  i18n,
+  state::AppState,
+  storage::{
+    parse_timestamp, ReminderKind, ReminderRecord, ReminderSetting, StorageError, TodoItem,
+  },
+};
+
+pub const REMINDER_EVENT: &str = "reminder:fired";
+
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(30);
+/// 一次补发超过该数量时合并为一条通知，避免刷屏。
+const MAX_INDIVIDUAL_NOTIFICATIONS: usize = 3;
+
+/// 截止/计划时间提醒的调度器，排期结果持久化在 `reminders.json`。
+#[derive(Default)]
+pub struct ReminderScheduler {
+  guard: Mutex<()>,
+}
+
+impl ReminderScheduler {
+  /// 根据当前待办与设置重新计算排期，保留未变化提醒的已发送/延后状态。
+  pub fn sync(&self, state: &AppState) -> Result<Vec<ReminderRecord>, StorageError> {
+    let _lock = self.guard.lock();
+    let settings = state.store().load_settings()?;
+    let todos = state.store().load_todos()?;
+    let existing = state.store().load_reminders()?;
+    let records = reconcile(&todos, &settings.reminders, existing.clone(), Utc::now());
+    // 轮询时排期通常没有变化，只在变化时写盘。
+    if records != existing {
+      state.store().save_reminders(&records)?;
+    }
+    Ok(records)
+  }
+
+  pub fn snooze(
+    &self,
+    state: &AppState,
+    id: &str,
+    minutes: u32,
+  ) -> Result<ReminderRecord, StorageError> {
+    if minutes == 0 || minutes > 7 * 24 * 60 {
+      return Err(StorageError::validation(i18n::t!("reminder.invalidSnooze")));
+    }
+    self.update(state, id, |record| {
+      record.snoozed_until =
+        Some((Utc::now() + Duration::minutes(i64::from(minutes))).to_rfc3339());
+      record.fired_at = None;
+      record.dismissed = false;
+    })
+  }
+
+  pub fn dismiss(&self, state: &AppState, id: &str) -> Result<ReminderRecord, StorageError> {
+    self.update(state, id, |record| {
+      record.dismissed = true;
+      record.snoozed_until = None;
+    })
+  }
+
+  fn update(
+    &self,
+    state: &AppState,
+    id: &str,
+    apply: impl FnOnce(&mut ReminderRecord),
+  ) -> Result<ReminderRecord, StorageError> {
+    let _lock = self.guard.lock();
+    let mut records = state.store().load_reminders()?;
+    let record = records
+      .iter_mut()
+      .find(|record| record.id == id)
+      .ok_or(StorageError::NotFound("reminder"))?;
+    apply(record);
+    let updated = record.clone();
+    state.store().save_reminders(&records)?;
+    Ok(updated)
+  }
+
+  /// 取出所有到期的提醒并标记为已发送；过期太久的直接跳过。
+  fn take_due(
+    &self,
+    state: &AppState,
+    now: DateTime<Utc>,
+  ) -> Result<Vec<ReminderRecord>, StorageError> {
+    let _lock = self.guard.lock();
+    let settings = state.store().load_settings()?;
+    let mut records = state.store().load_reminders()?;
+    let catch_up = Duration::hours(i64::from(settings.reminders.catch_up_hours));
+    let mut due = Vec::new();
+    let mut changed = false;
+    for record in records.iter_mut().filter(|record| is_pending(record)) {
+      let Some(fire_at) = effective_fire_time(record) else {
+        continue;
+      };
+      if fire_at > now {
+        continue;
+      }
+      record.fired_at = Some(now.to_rfc3339());
+      record.snoozed_until = None;
+      changed = true;
+      if now - fire_at <= catch_up {
+        due.push(record.clone());
+      }
+    }
+    if changed {
+      state.store().save_reminders(&records)?;
+    }
+    Ok(due)
+  }
+}
+
+fn is_pending(record: &ReminderRecord) -> bool {
+  record.fired_at.is_none() && !record.dismissed
+}
+
+fn effective_fire_time(record: &ReminderRecord) -> Option<DateTime<Utc>> {
+  parse_timestamp(record.snoozed_until.as_deref().unwrap_or(&record.fire_at))
+}
+
+fn reminder_id(todo_id: &str, kind: ReminderKind, offset: u32) -> String {
+  let kind = match kind {
+    ReminderKind::Due => "due",
+    ReminderKind::Planned => "planned",
+  };
+  format!("{todo_id}:{kind}:{offset}")
+}
+
+fn reconcile(
+  todos: &[TodoItem],
+  setting: &ReminderSetting,
+  existing: Vec<ReminderRecord>,
+  now: DateTime<Utc>,
+) -> Vec<ReminderRecord> {
+  if !setting.enabled {
+    return Vec::new();
+  }
+  let mut previous: HashMap<String, ReminderRecord> = existing
+    .into_iter()
+    .map(|record| (record.id.clone(), record))
+    .collect();
+  let mut records = Vec::new();
+
+  for todo in todos.iter().filter(|todo| !todo.completed) {
+    let overrides = todo.reminder_override.clone().unwrap_or_default();
+    let plans = [
+      (
+        ReminderKind::Due,
+        todo.due_time(),
+        overrides
+          .due_offsets_minutes
+          .unwrap_or_else(|| setting.due_offsets_minutes.clone()),
+      ),
+      (
+        ReminderKind::Planned,
+        todo.planned_time(),
+        overrides
+          .planned_offsets_minutes
+          .unwrap_or_else(|| setting.planned_offsets_minutes.clone()),
+      ),
+    ];
+    for (kind, target, offsets) in plans {
+      let Some(target) = target else {
+        continue;
+      };
+      let mut offsets = offsets;
+      offsets.sort_unstable();
+      offsets.dedup();
+      for offset in offsets {
+        let id = reminder_id(&todo.id, kind, offset);
+        let fire_at = target - Duration::minutes(i64::from(offset));
+        let target_at = target.to_rfc3339();
+        match previous.remove(&id) {
+          Some(record) if record.target_at == target_at => records.push(record),
+          _ => records.push(ReminderRecord {
+            id,
+            todo_id: todo.id.clone(),
+            kind,
+            offset_minutes: offset,
+            target_at,
+            fire_at: fire_at.to_rfc3339(),
+            snoozed_until: None,
+            // 新建时已经错过的提醒不再补发，例如临近截止才创建的待办。
+            fired_at: (fire_at < now - Duration::minutes(1)).then(|| now.to_rfc3339()),
+            dismissed: false,
+          }),
+        }
+      }
+    }
+  }
+  records.sort_by(|a, b| a.fire_at.cmp(&b.fire_at));
+  records
+}
+
+/// 启动提醒线程：定期同步排期并发送到期通知。
+///
+/// 休眠期间错过的提醒在唤醒后的第一次轮询中到期，`catch_up_hours` 以内的照常补发。
+pub fn spawn(app: AppHandle) {
+  thread::spawn(move || loop {
+    let state = app.state::<AppState>();
+    if let Err(err) = state.reminders().sync(&state) {
+      log::warn!("同步提醒排期失败: {}", err);
+    }
+    match state.reminders().take_due(&state, Utc::now()) {
+      Ok(due) if !due.is_empty() => notify(&app, &state, &due),
+      Ok(_) => {}
+      Err(err) => log::warn!("读取到期提醒失败: {}", err),
+    }
+    thread::sleep(POLL_INTERVAL);
+  });
+}
+
+fn notify(app: &AppHandle, state: &AppState, due: &[ReminderRecord]) {
+  let titles: HashMap<String, String> = state
+    .store()
+    .load_todos()
+    .unwrap_or_default()
+    .into_iter()
+    .map(|todo| (todo.id, todo.title))
+    .collect();
+
+  let messages: Vec<(String, String)> = due
+    .iter()
+    .map(|record| {
+      let title = titles
+        .get(&record.todo_id)
+        .cloned()
+        .unwrap_or_else(|| i18n::t!("reminder.untitled"));
+      (title, describe(record))
+    })
+    .collect();
+
+  if messages.len() > MAX_INDIVIDUAL_NOTIFICATIONS {
+    let body = messages
+      .iter()
+      .map(|(title, detail)| i18n::t!("reminder.line", title = title, detail = detail))
+      .collect::<Vec<_>>()
+      .join("\n");
+    show(
+      app,
+      &i18n::t!("reminder.summary", count = messages.len()),
+      &body,
+    );
+  } else {
+    for (title, detail) in &messages {
+      show(app, title, detail);
+    }
+  }
+  let _ = app.emit(REMINDER_EVENT, due);
+}
+
+fn show(app: &AppHandle, title: &str, body: &str) {
+  if let Err(err) = app.notification().builder().title(title).body(body).show() {
+    log::warn!("发送提醒通知失败: {}", err);
+  }
+}
+
+fn describe(record: &ReminderRecord) -> String {
+  let target = parse_timestamp(&record.target_at)
+    .map(|at| at.with_timezone(&Local).format("%m-%d %H:%M").to_string())
+    .unwrap_or_default();
+  match (record.kind, record.offset_minutes == 0) {
+    (ReminderKind::Due, true) => i18n::t!("reminder.dueNow", target = target),
+    (ReminderKind::Due, false) => i18n::t!("reminder.dueAt", target = target),
+    (ReminderKind::Planned, true) => i18n::t!("reminder.plannedNow", target = target),
+    (ReminderKind::Planned, false) => i18n::t!("reminder.plannedAt", target = target),
+  }
+}
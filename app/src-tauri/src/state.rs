@@ -1,15 +1,57 @@
-use crate::storage::FileStore;
+use crate::{
+  hotkeys::HotkeyRegistry, http_api::HttpApiServer, reminders::ReminderScheduler,
+  storage::FileStore, timer::PomodoroTimer, tray::TrayController, windows::WindowRegistry,
+};
 
 pub struct AppState {
   store: FileStore,
+  timer: PomodoroTimer,
+  http_api: HttpApiServer,
+  reminders: ReminderScheduler,
+  hotkeys: HotkeyRegistry,
+  tray: TrayController,
+  windows: WindowRegistry,
 }
 
 impl AppState {
   pub fn new(store: FileStore) -> Self {
-    Self { store }
+    let config = store.load_pomodoro_config().unwrap_or_default();
+    Self {
+      store,
+      timer: PomodoroTimer::new(&config),
+      http_api: HttpApiServer::default(),
+      reminders: ReminderScheduler::default(),
+      hotkeys: HotkeyRegistry::default(),
+      tray: TrayController::default(),
+      windows: WindowRegistry::default(),
+    }
   }
 
   pub fn store(&self) -> &FileStore {
     &self.store
   }
+
+  pub fn timer(&self) -> &PomodoroTimer {
+    &self.timer
+  }
+
+  pub fn http_api(&self) -> &HttpApiServer {
+    &self.http_api
+  }
+
+  pub fn reminders(&self) -> &ReminderScheduler {
+    &self.reminders
+  }
+
+  pub fn hotkeys(&self) -> &HotkeyRegistry {
+    &self.hotkeys
+  }
+
+  pub fn tray(&self) -> &TrayController {
+    &self.tray
+  }
+
+  pub fn windows(&self) -> &WindowRegistry {
+    &self.windows
+  }
 }
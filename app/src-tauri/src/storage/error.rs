@@ -1,23 +1,110 @@
-use std::io;
+use std::{
+  fmt, io,
+  path::{Path, PathBuf},
+};
 
+use serde::Serialize;
 use thiserror::Error;
 
+use crate::i18n;
+
+/// 出错时正在进行的文件操作。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileOperation {
+  Read,
+  Write,
+  Backup,
+  CreateDir,
+  Replace,
+}
+
+impl fmt::Display for FileOperation {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&match self {
+      FileOperation::Read => i18n::t!("fileOperation.read"),
+      FileOperation::Write => i18n::t!("fileOperation.write"),
+      FileOperation::Backup => i18n::t!("fileOperation.backup"),
+      FileOperation::CreateDir => i18n::t!("fileOperation.createDir"),
+      FileOperation::Replace => i18n::t!("fileOperation.replace"),
+    })
+  }
+}
+
+/// 错误文案按当前界面语言渲染，见 [`i18n`]。
 #[derive(Debug, Error)]
 pub enum StorageError {
-  #[error("无法解析应用数据目录")]
   ResolveDir,
-  #[error("IO错误: {0}")]
   Io(#[from] io::Error),
-  #[error("JSON 编解码失败: {0}")]
+  File {
+    operation: FileOperation,
+    path: PathBuf,
+    source: io::Error,
+  },
   Json(#[from] serde_json::Error),
-  #[error("数据不存在: {0}")]
+  Corrupt {
+    path: PathBuf,
+    source: serde_json::Error,
+  },
   NotFound(&'static str),
-  #[error("数据校验失败: {0}")]
-  Validation(String),
+  Validation {
+    message: String,
+    /// 出错字段的路径，例如 `hotkeys.skipPhase`。
+    field: Option<String>,
+  },
+  /// 系统服务或第三方库（开机启动插件、zip 等）返回的非 IO 错误，文案在构造时已本地化。
+  External(String),
+}
+
+impl fmt::Display for StorageError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(&match self {
+      StorageError::ResolveDir => i18n::t!("error.resolveDir"),
+      StorageError::Io(error) => i18n::t!("error.io", error = error),
+      StorageError::File {
+        operation,
+        path,
+        source,
+      } => i18n::t!(
+        "error.file",
+        operation = operation,
+        path = path.display(),
+        error = source
+      ),
+      StorageError::Json(error) => i18n::t!("error.json", error = error),
+      StorageError::Corrupt { path, source } => {
+        i18n::t!("error.corrupt", path = path.display(), error = source)
+      }
+      StorageError::NotFound(entity) => i18n::t!("error.notFound", entity = entity),
+      StorageError::Validation { message, .. } => {
+        i18n::t!("error.validation", message = message)
+      }
+      StorageError::External(message) => message.clone(),
+    })
+  }
 }
 
 impl StorageError {
   pub fn validation<T: Into<String>>(msg: T) -> Self {
-    StorageError::Validation(msg.into())
+    StorageError::Validation {
+      message: msg.into(),
+      field: None,
+    }
+  }
+
+  pub fn invalid_field<F: Into<String>, T: Into<String>>(field: F, msg: T) -> Self {
+    StorageError::Validation {
+      message: msg.into(),
+      field: Some(field.into()),
+    }
+  }
+
+  /// 给 IO 错误补上出错的文件与操作，用于 `map_err`。
+  pub fn file(operation: FileOperation, path: &Path) -> impl FnOnce(io::Error) -> Self + '_ {
+    move |source| StorageError::File {
+      operation,
+      path: path.to_path_buf(),
+      source,
+    }
   }
 }
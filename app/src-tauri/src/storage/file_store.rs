@@ -9,17 +9,27 @@ use parking_lot::Mutex;
 use serde::{de::DeserializeOwned, Serialize};
 use tauri::{AppHandle, Manager};
 
-use super::{PomodoroConfig, PomodoroSession, StorageError, TodoItem, UserSettings};
+use super::{
+  CalendarData, FileOperation, PomodoroConfig, PomodoroSession, ReminderRecord, StorageError,
+  TodoItem, TodoLink, UserSettings,
+};
 
 const TODOS_FILE: &str = "todos.json";
 const POMODORO_FILE: &str = "pomodoro.json";
 const SETTINGS_FILE: &str = "settings.json";
 const SESSIONS_FILE: &str = "sessions.json";
+const CALENDAR_FILE: &str = "calendar_events.json";
+const REMINDERS_FILE: &str = "reminders.json";
+const LINKS_FILE: &str = "links.json";
 
 pub struct FileStore {
   data_dir: Arc<PathBuf>,
   backup_dir: Arc<PathBuf>,
   guard: Mutex<()>,
+  /// 串行化设置的“读取-修改-写回”，避免多个窗口同时修改不同字段时互相覆盖。
+  settings_guard: Mutex<()>,
+  /// 串行化待办的“读取-修改-写回”，界面与本地 HTTP API 同时修改时不会丢失其中一次。
+  todos_guard: Mutex<()>,
 }
 
 impl FileStore {
@@ -31,27 +41,47 @@ impl FileStore {
     let data_dir = base_dir.join("data");
     let backup_dir = data_dir.join("backups");
 
-    fs::create_dir_all(&data_dir)?;
-    fs::create_dir_all(&backup_dir)?;
+    fs::create_dir_all(&data_dir)
+      .map_err(StorageError::file(FileOperation::CreateDir, &data_dir))?;
+    fs::create_dir_all(&backup_dir)
+      .map_err(StorageError::file(FileOperation::CreateDir, &backup_dir))?;
 
     let store = Self {
       data_dir: Arc::new(data_dir),
       backup_dir: Arc::new(backup_dir),
       guard: Mutex::new(()),
+      settings_guard: Mutex::new(()),
+      todos_guard: Mutex::new(()),
     };
 
     store.bootstrap_files()?;
     Ok(store)
   }
 
+  pub fn data_dir(&self) -> &Path {
+    &self.data_dir
+  }
+
   pub fn load_todos(&self) -> Result<Vec<TodoItem>, StorageError> {
     self.read_json::<Vec<TodoItem>>(TODOS_FILE)
   }
 
-  pub fn save_todos(&self, todos: &[TodoItem]) -> Result<(), StorageError> {
+  fn save_todos(&self, todos: &[TodoItem]) -> Result<(), StorageError> {
     self.write_json(TODOS_FILE, todos)
   }
 
+  /// 在待办锁内读取、修改并保存全部待办；`update` 返回错误时不写盘。
+  pub fn update_todos<T, F>(&self, update: F) -> Result<T, StorageError>
+  where
+    F: FnOnce(&mut Vec<TodoItem>) -> Result<T, StorageError>,
+  {
+    let _lock = self.todos_guard.lock();
+    let mut todos = self.load_todos()?;
+    let result = update(&mut todos)?;
+    self.save_todos(&todos)?;
+    Ok(result)
+  }
+
   pub fn load_pomodoro_config(&self) -> Result<PomodoroConfig, StorageError> {
     self.read_json::<PomodoroConfig>(POMODORO_FILE)
   }
@@ -76,6 +106,42 @@ impl FileStore {
     self.write_json(SETTINGS_FILE, settings)
   }
 
+  /// 在设置锁内读取、修改并保存设置；`update` 返回错误时不写盘。
+  pub fn update_settings<F>(&self, update: F) -> Result<UserSettings, StorageError>
+  where
+    F: FnOnce(&mut UserSettings) -> Result<(), StorageError>,
+  {
+    let _lock = self.settings_guard.lock();
+    let mut settings = self.load_settings()?;
+    update(&mut settings)?;
+    self.save_settings(&settings)?;
+    Ok(settings)
+  }
+
+  pub fn load_calendar(&self) -> Result<CalendarData, StorageError> {
+    self.read_json::<CalendarData>(CALENDAR_FILE)
+  }
+
+  pub fn save_calendar(&self, calendar: &CalendarData) -> Result<(), StorageError> {
+    self.write_json(CALENDAR_FILE, calendar)
+  }
+
+  pub fn load_reminders(&self) -> Result<Vec<ReminderRecord>, StorageError> {
+    self.read_json::<Vec<ReminderRecord>>(REMINDERS_FILE)
+  }
+
+  pub fn save_reminders(&self, reminders: &[ReminderRecord]) -> Result<(), StorageError> {
+    self.write_json(REMINDERS_FILE, reminders)
+  }
+
+  pub fn load_links(&self) -> Result<Vec<TodoLink>, StorageError> {
+    self.read_json::<Vec<TodoLink>>(LINKS_FILE)
+  }
+
+  pub fn save_links(&self, links: &[TodoLink]) -> Result<(), StorageError> {
+    self.write_json(LINKS_FILE, links)
+  }
+
   fn bootstrap_files(&self) -> Result<(), StorageError> {
     if !self.path_for(TODOS_FILE).exists() {
       self.write_json(TODOS_FILE, &Vec::<TodoItem>::new())?;
@@ -101,11 +167,18 @@ impl FileStore {
     if !path.exists() {
       return Ok(T::default());
     }
-    let content = fs::read_to_string(&path)?;
+    let content =
+      fs::read_to_string(&path).map_err(StorageError::file(FileOperation::Read, &path))?;
     if content.trim().is_empty() {
       return Ok(T::default());
     }
-    Ok(serde_json::from_str::<T>(&content)?)
+    serde_json::from_str::<T>(&content).map_err(|source| {
+      log::error!("解析数据文件 {} 失败: {}", path.display(), source);
+      StorageError::Corrupt {
+        path: path.clone(),
+        source,
+      }
+    })
   }
 
   fn write_json<T>(&self, name: &str, data: &T) -> Result<(), StorageError>
@@ -115,18 +188,19 @@ impl FileStore {
     let _lock = self.guard.lock();
     let path = self.path_for(name);
     if let Some(parent) = path.parent() {
-      fs::create_dir_all(parent)?;
+      fs::create_dir_all(parent).map_err(StorageError::file(FileOperation::CreateDir, parent))?;
     }
 
     self.maybe_backup(&path, name)?;
 
     let payload = serde_json::to_vec_pretty(data)?;
     let temp_path = path.with_extension("tmp");
-    fs::write(&temp_path, &payload)?;
+    fs::write(&temp_path, &payload)
+      .map_err(StorageError::file(FileOperation::Write, &temp_path))?;
     if path.exists() {
-      fs::remove_file(&path)?;
+      fs::remove_file(&path).map_err(StorageError::file(FileOperation::Replace, &path))?;
     }
-    fs::rename(&temp_path, &path)?;
+    fs::rename(&temp_path, &path).map_err(StorageError::file(FileOperation::Replace, &path))?;
     Ok(())
   }
 
@@ -144,10 +218,12 @@ impl FileStore {
     }
 
     if let Some(parent) = backup_path.parent() {
-      fs::create_dir_all(parent)?;
+      fs::create_dir_all(parent).map_err(StorageError::file(FileOperation::CreateDir, parent))?;
     }
 
-    fs::copy(path, backup_path)?;
+    fs::copy(path, &backup_path)
+      .map_err(StorageError::file(FileOperation::Backup, &backup_path))?;
+    log::info!("已备份数据文件: {}", backup_path.display());
     Ok(())
   }
 
@@ -2,9 +2,13 @@ mod error;
 mod file_store;
 mod models;
 
-pub use error::StorageError;
+pub use error::{FileOperation, StorageError};
 pub use file_store::FileStore;
 pub use models::{
-  PomodoroConfig, PomodoroSession, PomodoroSessionDraft,
-  TodoDraft, TodoItem, UserSettings, WindowGeometry,
+  parse_timestamp, CalendarData, CalendarEvent, CalendarSource, CloseBehavior, FloatingMode,
+  HotkeySetting, HttpApiSetting, IcsFeedSetting, Language, LinkKind, LogLevel, PomodoroConfig,
+  PomodoroSession, PomodoroSessionDraft, PomodoroSessionKind, ReminderKind, ReminderOverride,
+  ReminderRecord, ReminderSetting, RoundingMode, RoundingRule, StatusChange, TodoDraft, TodoItem,
+  TodoLink, TodoPriority, UserSettings, WindowGeometry, WindowPreference, WorkflowSetting,
+  WorkflowStatus,
 };
@@ -1,16 +1,25 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum TodoPriority {
   Low,
+  #[default]
   Medium,
   High,
 }
 
-impl Default for TodoPriority {
-  fn default() -> Self {
-    Self::Medium
+impl TodoPriority {
+  /// 排序用的次序，高优先级在前。
+  pub fn rank(&self) -> u8 {
+    match self {
+      TodoPriority::High => 0,
+      TodoPriority::Medium => 1,
+      TodoPriority::Low => 2,
+    }
   }
 }
 
@@ -26,6 +35,8 @@ pub struct TodoItem {
   #[serde(default)]
   pub tags: Vec<String>,
   #[serde(default)]
+  pub project: Option<String>,
+  #[serde(default)]
   pub planned_at: Option<String>,
   #[serde(default)]
   pub due_at: Option<String>,
@@ -33,10 +44,43 @@ pub struct TodoItem {
   pub completed: bool,
   #[serde(default)]
   pub completed_at: Option<String>,
+  #[serde(default)]
+  pub reminder_override: Option<ReminderOverride>,
+  /// 计划日期被顺延到后一天的次数。
+  #[serde(default)]
+  pub deferred_count: u32,
+  /// 工作流状态 id；旧数据为空，按 `completed` 推断。
+  #[serde(default)]
+  pub status: Option<String>,
+  #[serde(default)]
+  pub status_history: Vec<StatusChange>,
+  /// 手动排序键（分数索引），只在同一项目内比较。
+  #[serde(default)]
+  pub sort_key: Option<String>,
   pub created_at: String,
   pub updated_at: String,
 }
 
+impl TodoItem {
+  pub fn due_time(&self) -> Option<DateTime<Utc>> {
+    self.due_at.as_deref().and_then(parse_timestamp)
+  }
+
+  pub fn planned_time(&self) -> Option<DateTime<Utc>> {
+    self.planned_at.as_deref().and_then(parse_timestamp)
+  }
+}
+
+/// 一次状态变更记录。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusChange {
+  #[serde(default)]
+  pub from: Option<String>,
+  pub to: String,
+  pub at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TodoDraft {
@@ -48,11 +92,71 @@ pub struct TodoDraft {
   #[serde(default)]
   pub tags: Vec<String>,
   #[serde(default)]
+  pub project: Option<String>,
+  #[serde(default)]
   pub planned_at: Option<String>,
   #[serde(default)]
   pub due_at: Option<String>,
 }
 
+/// 单个待办的提醒设置，字段为空时沿用全局设置，空列表表示关闭该类提醒。
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderOverride {
+  #[serde(default)]
+  pub due_offsets_minutes: Option<Vec<u32>>,
+  #[serde(default)]
+  pub planned_offsets_minutes: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ReminderKind {
+  Due,
+  Planned,
+}
+
+/// 已排期的提醒，持久化以便重启后继续、休眠唤醒后补发。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderRecord {
+  pub id: String,
+  pub todo_id: String,
+  pub kind: ReminderKind,
+  pub offset_minutes: u32,
+  /// 计算提醒时依据的截止/计划时间，变化后需要重新排期。
+  pub target_at: String,
+  pub fire_at: String,
+  #[serde(default)]
+  pub snoozed_until: Option<String>,
+  #[serde(default)]
+  pub fired_at: Option<String>,
+  #[serde(default)]
+  pub dismissed: bool,
+}
+
+/// 待办之间的关联类型：`blocks` 表示 `from` 完成前 `to` 无法开始。
+///
+/// `blockedBy` 只用于创建，保存时换成方向相反的 `blocks`。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkKind {
+  Blocks,
+  BlockedBy,
+  Related,
+  DuplicateOf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TodoLink {
+  pub id: String,
+  pub kind: LinkKind,
+  pub from: String,
+  pub to: String,
+  pub created_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum PomodoroSessionKind {
@@ -98,6 +202,20 @@ pub struct PomodoroSession {
   pub completed: bool,
 }
 
+impl PomodoroSession {
+  pub fn start_time(&self) -> Option<DateTime<Utc>> {
+    parse_timestamp(&self.start_at)
+  }
+
+  /// 结束时间缺失时按开始时间加时长推算。
+  pub fn end_time(&self) -> Option<DateTime<Utc>> {
+    self.end_at.as_deref().and_then(parse_timestamp).or_else(|| {
+      let minutes = self.duration_minutes?;
+      Some(self.start_time()? + chrono::Duration::minutes(i64::from(minutes)))
+    })
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PomodoroSessionDraft {
@@ -112,21 +230,68 @@ pub struct PomodoroSessionDraft {
   pub completed: bool,
 }
 
+/// 从外部 `.ics` 导入的日程，只读。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+  pub id: String,
+  pub source_id: String,
+  #[serde(default)]
+  pub uid: Option<String>,
+  pub title: String,
+  #[serde(default)]
+  pub location: Option<String>,
+  pub start_at: String,
+  pub end_at: String,
+  #[serde(default)]
+  pub all_day: bool,
+  #[serde(default = "default_true")]
+  pub busy: bool,
+}
+
+impl CalendarEvent {
+  pub fn start_time(&self) -> Option<DateTime<Utc>> {
+    parse_timestamp(&self.start_at)
+  }
+
+  pub fn end_time(&self) -> Option<DateTime<Utc>> {
+    parse_timestamp(&self.end_at)
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct CalendarSource {
+  pub id: String,
+  pub path: String,
+  #[serde(default)]
+  pub watch: bool,
+  #[serde(default)]
+  pub modified_at: Option<String>,
+  pub imported_at: String,
+  #[serde(default)]
+  pub event_count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarData {
+  #[serde(default)]
+  pub sources: Vec<CalendarSource>,
+  #[serde(default)]
+  pub events: Vec<CalendarEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub enum ThemeMode {
   System,
   Light,
   Dark,
+  #[default]
   Mac,
 }
 
-impl Default for ThemeMode {
-  fn default() -> Self {
-    Self::Mac
-  }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct HotkeySetting {
@@ -134,6 +299,12 @@ pub struct HotkeySetting {
   pub toggle_floating: Option<String>,
   #[serde(default)]
   pub start_or_pause_timer: Option<String>,
+  #[serde(default)]
+  pub quick_add_todo: Option<String>,
+  #[serde(default)]
+  pub skip_phase: Option<String>,
+  #[serde(default)]
+  pub show_main_window: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -147,15 +318,262 @@ pub struct WindowGeometry {
   pub width: Option<u32>,
   #[serde(default)]
   pub height: Option<u32>,
+  /// 保存时窗口所在显示器的名称，用于恢复时判断显示器是否还在。
+  #[serde(default)]
+  pub monitor: Option<String>,
+  #[serde(default)]
+  pub scale_factor: Option<f64>,
+  #[serde(default)]
+  pub maximized: bool,
+  #[serde(default)]
+  pub visible: Option<bool>,
+}
+
+/// 按窗口标签保存的几何信息，序列化格式与旧版的 `{ main, floating }` 兼容。
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct WindowState(BTreeMap<String, WindowGeometry>);
+
+impl WindowState {
+  pub fn get(&self, label: &str) -> WindowGeometry {
+    self.0.get(label).cloned().unwrap_or_default()
+  }
+
+  pub fn set(&mut self, label: &str, geometry: WindowGeometry) {
+    self.0.insert(label.to_string(), geometry);
+  }
+}
+
+/// 按需打开的窗口各自的显示偏好；未设置的项沿用窗口类型的默认值。
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPreference {
+  #[serde(default)]
+  pub always_on_top: Option<bool>,
+  #[serde(default)]
+  pub visible_on_all_workspaces: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiSetting {
+  #[serde(default)]
+  pub enabled: bool,
+  #[serde(default = "default_http_api_port")]
+  pub port: u16,
+  #[serde(default)]
+  pub token: Option<String>,
+}
+
+fn default_http_api_port() -> u16 {
+  27270
+}
+
+impl Default for HttpApiSetting {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      port: default_http_api_port(),
+      token: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IcsFeedSetting {
+  #[serde(default)]
+  pub enabled: bool,
+  #[serde(default)]
+  pub folder: Option<String>,
+  #[serde(default = "default_ics_feed_interval")]
+  pub interval_minutes: u32,
+  #[serde(default = "default_true")]
+  pub include_sessions: bool,
+}
+
+fn default_ics_feed_interval() -> u32 {
+  15
+}
+
+fn default_true() -> bool {
+  true
+}
+
+impl Default for IcsFeedSetting {
+  fn default() -> Self {
+    Self {
+      enabled: false,
+      folder: None,
+      interval_minutes: default_ics_feed_interval(),
+      include_sessions: true,
+    }
+  }
+}
+
+/// 工作流中的一个状态；`done` 为真的状态视为已完成。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStatus {
+  pub id: String,
+  /// 显示名称；为空时按状态 id 使用当前语言的内置名称。
+  #[serde(default)]
+  pub name: String,
+  #[serde(default)]
+  pub done: bool,
+  /// 允许从该状态切换到的状态 id。
+  #[serde(default)]
+  pub transitions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowSetting {
+  pub statuses: Vec<WorkflowStatus>,
+  /// 新建或重新打开的待办所处的状态。
+  pub initial: String,
+}
+
+impl Default for WorkflowSetting {
+  fn default() -> Self {
+    let status = |id: &str, done: bool, transitions: &[&str]| WorkflowStatus {
+      id: id.to_string(),
+      name: String::new(),
+      done,
+      transitions: transitions.iter().map(|id| id.to_string()).collect(),
+    };
+    Self {
+      statuses: vec![
+        status("backlog", false, &["todo", "inProgress", "done"]),
+        status("todo", false, &["backlog", "inProgress", "blocked", "done"]),
+        status("inProgress", false, &["todo", "blocked", "done"]),
+        status("blocked", false, &["todo", "inProgress"]),
+        status("done", true, &["todo"]),
+      ],
+      initial: "todo".to_string(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RoundingMode {
+  #[default]
+  Up,
+  Down,
+  Nearest,
+}
+
+/// 工时取整规则；`increment_minutes` 为 0 时不取整。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoundingRule {
+  #[serde(default = "default_rounding_increment")]
+  pub increment_minutes: u32,
+  #[serde(default)]
+  pub mode: RoundingMode,
+  #[serde(default)]
+  pub minimum_minutes: u32,
+}
+
+fn default_rounding_increment() -> u32 {
+  15
+}
+
+impl Default for RoundingRule {
+  fn default() -> Self {
+    Self {
+      increment_minutes: default_rounding_increment(),
+      mode: RoundingMode::Up,
+      minimum_minutes: 0,
+    }
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
-pub struct WindowState {
+pub struct TimesheetSetting {
   #[serde(default)]
-  pub main: WindowGeometry,
+  pub rounding: RoundingRule,
   #[serde(default)]
-  pub floating: WindowGeometry,
+  pub output_folder: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderSetting {
+  #[serde(default = "default_true")]
+  pub enabled: bool,
+  #[serde(default = "default_due_offsets")]
+  pub due_offsets_minutes: Vec<u32>,
+  #[serde(default = "default_planned_offsets")]
+  pub planned_offsets_minutes: Vec<u32>,
+  /// 超过该时长仍未发出的提醒在补发时直接跳过。
+  #[serde(default = "default_catch_up_hours")]
+  pub catch_up_hours: u32,
+}
+
+fn default_due_offsets() -> Vec<u32> {
+  vec![24 * 60, 60, 0]
+}
+
+fn default_planned_offsets() -> Vec<u32> {
+  vec![0]
+}
+
+fn default_catch_up_hours() -> u32 {
+  24
+}
+
+impl Default for ReminderSetting {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      due_offsets_minutes: default_due_offsets(),
+      planned_offsets_minutes: default_planned_offsets(),
+      catch_up_hours: default_catch_up_hours(),
+    }
+  }
+}
+
+/// 写入日志的最低级别。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+  Error,
+  Warn,
+  #[default]
+  Info,
+  Debug,
+  Trace,
+}
+
+/// 后端错误、通知与托盘使用的语言。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Language {
+  #[default]
+  Zh,
+  En,
+}
+
+/// 悬浮窗的显示模式：紧凑模式只显示计时条，展开模式显示任务列表。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum FloatingMode {
+  Compact,
+  #[default]
+  Expanded,
+}
+
+/// 点击主窗口关闭按钮时的行为。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CloseBehavior {
+  #[default]
+  HideToTray,
+  Minimize,
+  Quit,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -165,14 +583,57 @@ pub struct UserSettings {
   pub follow_system_theme: bool,
   pub always_on_top: bool,
   pub snap_edge: bool,
+  /// 悬浮窗吸附到屏幕边缘的距离阈值（逻辑像素）。
+  #[serde(default = "default_snap_threshold")]
+  pub snap_threshold: u32,
+  /// 吸附到边缘后自动缩进屏幕外，鼠标移到露出的边缘时再展开。
+  #[serde(default)]
+  pub floating_auto_hide: bool,
+  /// 悬浮窗在所有虚拟桌面/工作区中可见。
+  #[serde(default)]
+  pub visible_on_all_workspaces: bool,
+  #[serde(default)]
+  pub close_behavior: CloseBehavior,
+  /// 启动时不显示主窗口，只留在系统托盘。
+  #[serde(default)]
+  pub start_hidden: bool,
+  /// 登录系统时自动启动。
+  #[serde(default)]
+  pub autostart: bool,
+  /// 开机自启时只留在系统托盘；手动打开时不受影响。
+  #[serde(default = "default_true")]
+  pub autostart_minimized: bool,
   #[serde(default = "default_floating_opacity")]
   pub floating_opacity: f32,
   #[serde(default)]
+  pub floating_mode: FloatingMode,
+  #[serde(default)]
   pub show_completed_in_floating: bool,
   #[serde(default)]
   pub hotkeys: HotkeySetting,
   #[serde(default)]
   pub window_state: WindowState,
+  /// 以窗口标签为键的窗口偏好，例如 `statistics`、`project-…`。
+  #[serde(default)]
+  pub window_preferences: BTreeMap<String, WindowPreference>,
+  #[serde(default)]
+  pub http_api: HttpApiSetting,
+  #[serde(default)]
+  pub ics_feed: IcsFeedSetting,
+  #[serde(default)]
+  pub timesheet: TimesheetSetting,
+  #[serde(default)]
+  pub reminders: ReminderSetting,
+  #[serde(default)]
+  pub log_level: LogLevel,
+  #[serde(default)]
+  pub language: Language,
+  #[serde(default)]
+  pub workflow: WorkflowSetting,
+}
+
+fn default_snap_threshold() -> u32 {
+  24
 }
 
 fn default_floating_opacity() -> f32 {
@@ -186,10 +647,46 @@ impl Default for UserSettings {
       follow_system_theme: true,
       always_on_top: true,
       snap_edge: true,
+      snap_threshold: default_snap_threshold(),
+      floating_auto_hide: false,
+      visible_on_all_workspaces: false,
+      close_behavior: CloseBehavior::default(),
+      start_hidden: false,
+      autostart: false,
+      autostart_minimized: true,
       floating_opacity: 0.95,
+      floating_mode: FloatingMode::default(),
       show_completed_in_floating: false,
       hotkeys: HotkeySetting::default(),
       window_state: WindowState::default(),
+      window_preferences: BTreeMap::new(),
+      http_api: HttpApiSetting::default(),
+      ics_feed: IcsFeedSetting::default(),
+      timesheet: TimesheetSetting::default(),
+      reminders: ReminderSetting::default(),
+      log_level: LogLevel::default(),
+      language: Language::default(),
+      workflow: WorkflowSetting::default(),
     }
   }
 }
+
+/// 解析前端写入的时间字符串：RFC 3339，或按本地时区理解的日期/日期时间。
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+  let value = value.trim();
+  if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+    return Some(parsed.with_timezone(&Utc));
+  }
+  let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M")
+    .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+    .ok()
+    .or_else(|| {
+      NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+    })?;
+  Local
+    .from_local_datetime(&naive)
+    .earliest()
+    .map(|local| local.with_timezone(&Utc))
+}
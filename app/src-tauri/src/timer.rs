@@ -0,0 +1,314 @@
+use std::{thread, time::Duration as StdDuration};
+
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::{
+  commands, i18n,
+  state::AppState,
+  storage::{PomodoroConfig, PomodoroSessionDraft, PomodoroSessionKind},
+  tray,
+};
+
+pub const TIMER_EVENT: &str = "pomodoro:timer";
+/// 计时器记录会话后广播，载荷为保存后的会话。
+pub const SESSION_EVENT: &str = "pomodoro:session";
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerStatus {
+  Idle,
+  Running,
+  Paused,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerSnapshot {
+  pub status: TimerStatus,
+  pub phase: PomodoroSessionKind,
+  pub duration_seconds: u64,
+  pub remaining_seconds: u64,
+  pub started_at: Option<String>,
+  pub todo_id: Option<String>,
+  pub focus_count: u32,
+}
+
+struct TimerInner {
+  status: TimerStatus,
+  phase: PomodoroSessionKind,
+  duration_seconds: u64,
+  remaining_seconds: u64,
+  /// 运行中阶段的结束时间；按系统时钟计算，睡眠期间同样计时。
+  deadline: Option<DateTime<Utc>>,
+  started_at: Option<String>,
+  todo_id: Option<String>,
+  focus_count: u32,
+}
+
+impl TimerInner {
+  fn remaining(&self) -> u64 {
+    match self.deadline {
+      Some(deadline) => {
+        let millis = (deadline - Utc::now()).num_milliseconds().max(0) as u64;
+        millis.div_ceil(1000)
+      }
+      None => self.remaining_seconds,
+    }
+  }
+
+  fn run(&mut self) {
+    self.status = TimerStatus::Running;
+    self.deadline = Some(Utc::now() + Duration::seconds(self.remaining_seconds as i64));
+  }
+
+  fn snapshot(&self) -> TimerSnapshot {
+    TimerSnapshot {
+      status: self.status,
+      phase: self.phase.clone(),
+      duration_seconds: self.duration_seconds,
+      remaining_seconds: self.remaining(),
+      started_at: self.started_at.clone(),
+      todo_id: self.todo_id.clone(),
+      focus_count: self.focus_count,
+    }
+  }
+
+  fn enter_phase(&mut self, phase: PomodoroSessionKind, config: &PomodoroConfig) {
+    self.duration_seconds = phase_seconds(&phase, config);
+    self.remaining_seconds = self.duration_seconds;
+    self.phase = phase;
+    self.status = TimerStatus::Idle;
+    self.deadline = None;
+    self.started_at = None;
+  }
+
+  fn finish_phase(&mut self, completed: bool) -> Option<PomodoroSessionDraft> {
+    let draft = self.started_at.take().map(|start_at| {
+      let elapsed = self.duration_seconds.saturating_sub(self.remaining());
+      // 睡眠唤醒后才处理到期的阶段时，以原定结束时间为准。
+      let end_at = self
+        .deadline
+        .map_or_else(Utc::now, |deadline| deadline.min(Utc::now()));
+      PomodoroSessionDraft {
+        todo_id: self.todo_id.clone(),
+        start_at,
+        end_at: Some(end_at.to_rfc3339()),
+        duration_minutes: Some((elapsed / 60) as u32),
+        r#type: self.phase.clone(),
+        completed,
+      }
+    });
+    if completed && self.phase == PomodoroSessionKind::Focus {
+      self.focus_count += 1;
+    }
+    draft
+  }
+
+  fn next_phase(&self, config: &PomodoroConfig) -> PomodoroSessionKind {
+    match self.phase {
+      PomodoroSessionKind::Focus
+        if config.long_break_interval > 0
+          && self.focus_count > 0
+          && self.focus_count % config.long_break_interval == 0 =>
+      {
+        PomodoroSessionKind::LongBreak
+      }
+      PomodoroSessionKind::Focus => PomodoroSessionKind::ShortBreak,
+      _ => PomodoroSessionKind::Focus,
+    }
+  }
+}
+
+/// 后端番茄钟计时器，供托盘、快捷键与本地 HTTP API 共享同一份状态。
+pub struct PomodoroTimer {
+  inner: Mutex<TimerInner>,
+}
+
+impl PomodoroTimer {
+  pub fn new(config: &PomodoroConfig) -> Self {
+    let duration = phase_seconds(&PomodoroSessionKind::Focus, config);
+    Self {
+      inner: Mutex::new(TimerInner {
+        status: TimerStatus::Idle,
+        phase: PomodoroSessionKind::Focus,
+        duration_seconds: duration,
+        remaining_seconds: duration,
+        deadline: None,
+        started_at: None,
+        todo_id: None,
+        focus_count: 0,
+      }),
+    }
+  }
+
+  pub fn snapshot(&self) -> TimerSnapshot {
+    self.inner.lock().snapshot()
+  }
+
+  /// 开始或继续当前阶段；传入 `phase` 时会先切换到该阶段，
+  /// 切换前已开始的阶段作为会话返回（已到期的算作完成）。
+  pub fn start(
+    &self,
+    config: &PomodoroConfig,
+    phase: Option<PomodoroSessionKind>,
+    todo_id: Option<String>,
+  ) -> (TimerSnapshot, Option<PomodoroSessionDraft>) {
+    let mut inner = self.inner.lock();
+    let mut draft = None;
+    if let Some(phase) = phase {
+      if phase != inner.phase || inner.status == TimerStatus::Idle {
+        let expired = inner.status == TimerStatus::Running && inner.remaining() == 0;
+        draft = inner.finish_phase(expired);
+        inner.enter_phase(phase, config);
+      }
+    }
+    if todo_id.is_some() {
+      inner.todo_id = todo_id;
+    }
+    if inner.status != TimerStatus::Running {
+      if inner.started_at.is_none() {
+        inner.started_at = Some(Utc::now().to_rfc3339());
+      }
+      inner.run();
+    }
+    (inner.snapshot(), draft)
+  }
+
+  pub fn pause(&self) -> TimerSnapshot {
+    let mut inner = self.inner.lock();
+    if inner.status == TimerStatus::Running {
+      inner.remaining_seconds = inner.remaining();
+      inner.deadline = None;
+      inner.status = TimerStatus::Paused;
+    }
+    inner.snapshot()
+  }
+
+  /// 未开始的阶段按新配置更新时长，返回变化后的状态。
+  pub fn apply_config(&self, config: &PomodoroConfig) -> Option<TimerSnapshot> {
+    let mut inner = self.inner.lock();
+    if inner.status != TimerStatus::Idle {
+      return None;
+    }
+    let phase = inner.phase.clone();
+    inner.enter_phase(phase, config);
+    Some(inner.snapshot())
+  }
+
+  pub fn toggle(&self, config: &PomodoroConfig) -> TimerSnapshot {
+    if self.snapshot().status == TimerStatus::Running {
+      self.pause()
+    } else {
+      self.start(config, None, None).0
+    }
+  }
+
+  /// 重置当前阶段，传入 `phase` 时切换到该阶段但不开始；已开始的阶段会作为未完成记录返回。
+  pub fn reset(
+    &self,
+    config: &PomodoroConfig,
+    phase: Option<PomodoroSessionKind>,
+  ) -> (TimerSnapshot, Option<PomodoroSessionDraft>) {
+    let mut inner = self.inner.lock();
+    let draft = inner.finish_phase(false);
+    let phase = phase.unwrap_or_else(|| inner.phase.clone());
+    inner.enter_phase(phase, config);
+    (inner.snapshot(), draft)
+  }
+
+  /// 跳过当前阶段并进入下一阶段，不自动开始。
+  pub fn skip(&self, config: &PomodoroConfig) -> (TimerSnapshot, Option<PomodoroSessionDraft>) {
+    let mut inner = self.inner.lock();
+    let draft = inner.finish_phase(false);
+    let next = inner.next_phase(config);
+    inner.enter_phase(next, config);
+    (inner.snapshot(), draft)
+  }
+
+  /// 推进计时；阶段结束时返回需要记录的会话。
+  fn tick(&self, config: &PomodoroConfig) -> (TimerSnapshot, Option<PomodoroSessionDraft>) {
+    let mut inner = self.inner.lock();
+    if inner.status != TimerStatus::Running || inner.remaining() > 0 {
+      return (inner.snapshot(), None);
+    }
+    let draft = inner.finish_phase(true);
+    let next = inner.next_phase(config);
+    inner.enter_phase(next, config);
+    if config.auto_start_next {
+      inner.started_at = Some(Utc::now().to_rfc3339());
+      inner.run();
+    }
+    (inner.snapshot(), draft)
+  }
+}
+
+pub fn phase_seconds(phase: &PomodoroSessionKind, config: &PomodoroConfig) -> u64 {
+  let minutes = match phase {
+    PomodoroSessionKind::Focus => config.focus_minutes,
+    PomodoroSessionKind::ShortBreak => config.short_break_minutes,
+    PomodoroSessionKind::LongBreak => config.long_break_minutes,
+  };
+  u64::from(minutes) * 60
+}
+
+/// 启动后台计时线程，每秒推进一次并向前端广播状态。
+pub fn spawn_ticker(app: AppHandle) {
+  thread::spawn(move || loop {
+    thread::sleep(StdDuration::from_secs(1));
+    let state = app.state::<AppState>();
+    if state.timer().snapshot().status != TimerStatus::Running {
+      continue;
+    }
+    let config = state.store().load_pomodoro_config().unwrap_or_default();
+    let (snapshot, finished) = state.timer().tick(&config);
+    if let Some(draft) = finished {
+      log::info!("{:?} 阶段结束，进入 {:?}", draft.r#type, snapshot.phase);
+      record_session(&app, draft);
+    }
+    publish(&app, &snapshot);
+  });
+}
+
+pub fn record_session(app: &AppHandle, draft: PomodoroSessionDraft) {
+  match commands::pomodoro::append_pomodoro_session(app.state(), draft) {
+    Ok(session) => {
+      if session.completed {
+        notify_completed(app, &session.r#type);
+      }
+      if let Err(err) = app.emit(SESSION_EVENT, &session) {
+        log::warn!("广播番茄钟会话失败: {}", err);
+      }
+    }
+    Err(err) => log::warn!("记录番茄钟会话失败: {}", err),
+  }
+}
+
+/// 阶段完成时发送系统通知，提示音由前端在收到会话事件后播放。
+fn notify_completed(app: &AppHandle, phase: &PomodoroSessionKind) {
+  let (title, body) = match phase {
+    PomodoroSessionKind::Focus => (
+      i18n::t!("pomodoro.focusDone"),
+      i18n::t!("pomodoro.focusDoneBody"),
+    ),
+    PomodoroSessionKind::ShortBreak => (
+      i18n::t!("pomodoro.shortBreakDone"),
+      i18n::t!("pomodoro.shortBreakDoneBody"),
+    ),
+    PomodoroSessionKind::LongBreak => (
+      i18n::t!("pomodoro.longBreakDone"),
+      i18n::t!("pomodoro.longBreakDoneBody"),
+    ),
+  };
+  if let Err(err) = app.notification().builder().title(title).body(body).show() {
+    log::warn!("发送番茄钟通知失败: {}", err);
+  }
+}
+
+pub fn publish(app: &AppHandle, snapshot: &TimerSnapshot) {
+  let _ = app.emit(TIMER_EVENT, snapshot);
+  tray::refresh(app);
+}
@@ -0,0 +1,402 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  i18n,
+  storage::{PomodoroSession, PomodoroSessionKind, RoundingMode, RoundingRule, TodoItem},
+};
+
+/// 未关联待办（或待办没有标签、项目）的记录使用的分组键；真实分组名去掉空白后不会为空。
+const UNASSIGNED: &str = "";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum TimesheetGrouping {
+  #[default]
+  Todo,
+  Tag,
+  Project,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimesheetRow {
+  pub date: String,
+  pub group: String,
+  pub sessions: u32,
+  pub tracked_minutes: f64,
+  pub billed_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Timesheet {
+  pub from: String,
+  pub to: String,
+  pub grouping: TimesheetGrouping,
+  pub rows: Vec<TimesheetRow>,
+  /// 不重复计数的专注次数；多标签的记录会出现在多行中，不能直接累加各行。
+  pub total_sessions: u32,
+  pub total_tracked_minutes: f64,
+  pub total_billed_minutes: u32,
+}
+
+#[derive(Default)]
+struct Bucket {
+  label: String,
+  sessions: u32,
+  seconds: f64,
+}
+
+/// 按本地日期和分组汇总已完成的专注记录；取整规则作用于每一行。
+pub fn build(
+  from: NaiveDate,
+  to: NaiveDate,
+  grouping: TimesheetGrouping,
+  todos: &[TodoItem],
+  sessions: &[PomodoroSession],
+  rounding: &RoundingRule,
+) -> Timesheet {
+  let todo_by_id: HashMap<&str, &TodoItem> =
+    todos.iter().map(|todo| (todo.id.as_str(), todo)).collect();
+  let mut buckets: BTreeMap<(NaiveDate, String), Bucket> = BTreeMap::new();
+  let mut total_sessions = 0;
+
+  for session in sessions
+    .iter()
+    .filter(|session| session.completed && session.r#type == PomodoroSessionKind::Focus)
+  {
+    let Some(start) = session.start_time() else {
+      continue;
+    };
+    let date = start.with_timezone(&Local).date_naive();
+    if date < from || date > to {
+      continue;
+    }
+    let seconds = match session.end_time() {
+      Some(end) if end > start => (end - start).num_seconds() as f64,
+      _ => f64::from(session.duration_minutes.unwrap_or(0)) * 60.0,
+    };
+    if seconds <= 0.0 {
+      continue;
+    }
+
+    let todo = session
+      .todo_id
+      .as_deref()
+      .and_then(|id| todo_by_id.get(id).copied());
+    let groups = group_keys(grouping, todo);
+    // 多个标签的记录平均分摊，保证各行合计等于实际专注时长。
+    let share = seconds / groups.len() as f64;
+    total_sessions += 1;
+    for (key, label) in groups {
+      let bucket = buckets.entry((date, key)).or_default();
+      bucket.label = label;
+      bucket.sessions += 1;
+      bucket.seconds += share;
+    }
+  }
+
+  let mut rows: Vec<TimesheetRow> = buckets
+    .into_iter()
+    .map(|((date, _), bucket)| {
+      let tracked_minutes = bucket.seconds / 60.0;
+      TimesheetRow {
+        date: date.format("%Y-%m-%d").to_string(),
+        group: bucket.label,
+        sessions: bucket.sessions,
+        tracked_minutes: (tracked_minutes * 100.0).round() / 100.0,
+        billed_minutes: round_minutes(tracked_minutes, rounding),
+      }
+    })
+    .collect();
+  rows.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.group.cmp(&b.group)));
+
+  Timesheet {
+    from: from.format("%Y-%m-%d").to_string(),
+    to: to.format("%Y-%m-%d").to_string(),
+    grouping,
+    total_sessions,
+    total_tracked_minutes: (rows.iter().map(|row| row.tracked_minutes).sum::<f64>() * 100.0)
+      .round()
+      / 100.0,
+    total_billed_minutes: rows.iter().map(|row| row.billed_minutes).sum(),
+    rows,
+  }
+}
+
+/// 分组的 `(键, 显示名)`：按待办分组时用 id 区分同名待办，其他分组的键即显示名。
+fn group_keys(grouping: TimesheetGrouping, todo: Option<&TodoItem>) -> Vec<(String, String)> {
+  let labels = match (grouping, todo) {
+    (TimesheetGrouping::Todo, Some(todo)) => {
+      return vec![(todo.id.clone(), todo.title.trim().to_string())];
+    }
+    (TimesheetGrouping::Tag, Some(todo)) => todo.tags.clone(),
+    (TimesheetGrouping::Project, Some(todo)) => todo.project.clone().into_iter().collect(),
+    (_, None) => Vec::new(),
+  };
+  let keys: Vec<(String, String)> = labels
+    .into_iter()
+    .map(|label| label.trim().to_string())
+    .filter(|label| !label.is_empty())
+    .map(|label| (label.clone(), label))
+    .collect();
+  if keys.is_empty() {
+    vec![(UNASSIGNED.to_string(), i18n::t!("timesheet.unassigned"))]
+  } else {
+    keys
+  }
+}
+
+pub fn round_minutes(minutes: f64, rule: &RoundingRule) -> u32 {
+  let increment = f64::from(rule.increment_minutes);
+  let rounded = if increment <= 0.0 {
+    minutes.round()
+  } else {
+    let units = minutes / increment;
+    let units = match rule.mode {
+      RoundingMode::Up => units.ceil(),
+      RoundingMode::Down => units.floor(),
+      RoundingMode::Nearest => units.round(),
+    };
+    units * increment
+  };
+  (rounded.max(0.0) as u32).max(rule.minimum_minutes)
+}
+
+pub fn to_csv(sheet: &Timesheet) -> String {
+  let mut out = headers(sheet)
+    .iter()
+    .map(|header| csv_field(header))
+    .collect::<Vec<_>>()
+    .join(",");
+  out.push_str("\r\n");
+  for row in &sheet.rows {
+    out.push_str(&format!(
+      "{},{},{},{:.2},{},{:.2}\r\n",
+      row.date,
+      csv_field(&row.group),
+      row.sessions,
+      row.tracked_minutes,
+      row.billed_minutes,
+      f64::from(row.billed_minutes) / 60.0
+    ));
+  }
+  out.push_str(&format!(
+    "{},,{},{:.2},{},{:.2}\r\n",
+    csv_field(&i18n::t!("timesheet.total")),
+    sheet.total_sessions,
+    sheet.total_tracked_minutes,
+    sheet.total_billed_minutes,
+    f64::from(sheet.total_billed_minutes) / 60.0
+  ));
+  out
+}
+
+pub fn to_markdown(sheet: &Timesheet) -> String {
+  let mut out = format!(
+    "# {}\n\n",
+    i18n::t!("timesheet.title", from = sheet.from, to = sheet.to)
+  );
+  out.push_str(&format!("| {} |\n", headers(sheet).join(" | ")));
+  out.push_str("| --- | --- | ---: | ---: | ---: | ---: |\n");
+  for row in &sheet.rows {
+    out.push_str(&format!(
+      "| {} | {} | {} | {:.2} | {} | {:.2} |\n",
+      row.date,
+      markdown_cell(&row.group),
+      row.sessions,
+      row.tracked_minutes,
+      row.billed_minutes,
+      f64::from(row.billed_minutes) / 60.0
+    ));
+  }
+  out.push_str(&format!(
+    "| **{}** | | {} | {:.2} | {} | {:.2} |\n",
+    i18n::t!("timesheet.total"),
+    sheet.total_sessions,
+    sheet.total_tracked_minutes,
+    sheet.total_billed_minutes,
+    f64::from(sheet.total_billed_minutes) / 60.0
+  ));
+  out
+}
+
+/// 表头按当前界面语言生成，分组列随分组方式变化。
+fn headers(sheet: &Timesheet) -> [String; 6] {
+  let group = match sheet.grouping {
+    TimesheetGrouping::Todo => i18n::t!("timesheet.groupTodo"),
+    TimesheetGrouping::Tag => i18n::t!("timesheet.groupTag"),
+    TimesheetGrouping::Project => i18n::t!("timesheet.groupProject"),
+  };
+  [
+    i18n::t!("timesheet.date"),
+    group,
+    i18n::t!("timesheet.sessions"),
+    i18n::t!("timesheet.trackedMinutes"),
+    i18n::t!("timesheet.billedMinutes"),
+    i18n::t!("timesheet.billedHours"),
+  ]
+}
+
+fn csv_field(value: &str) -> String {
+  if value.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
+
+fn markdown_cell(value: &str) -> String {
+  value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  fn rule(increment_minutes: u32, mode: RoundingMode, minimum_minutes: u32) -> RoundingRule {
+    RoundingRule {
+      increment_minutes,
+      mode,
+      minimum_minutes,
+    }
+  }
+
+  fn todo(id: &str, title: &str, project: Option<&str>, tags: &[&str]) -> TodoItem {
+    serde_json::from_value(json!({
+      "id": id,
+      "title": title,
+      "project": project,
+      "tags": tags,
+      "createdAt": "2026-03-01T00:00:00Z",
+      "updatedAt": "2026-03-01T00:00:00Z",
+    }))
+    .unwrap()
+  }
+
+  /// 中午（UTC）开始的专注，任何时区下本地日期都不变。
+  fn focus(todo_id: Option<&str>, day: u32, minutes: u32) -> PomodoroSession {
+    serde_json::from_value(json!({
+      "id": format!("{todo_id:?}-{day}-{minutes}"),
+      "todoId": todo_id,
+      "startAt": format!("2026-03-{day:02}T12:00:00Z"),
+      "durationMinutes": minutes,
+      "type": "focus",
+      "completed": true,
+    }))
+    .unwrap()
+  }
+
+  fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+  }
+
+  #[test]
+  fn rounds_by_mode_and_increment() {
+    assert_eq!(round_minutes(16.0, &rule(15, RoundingMode::Up, 0)), 30);
+    assert_eq!(round_minutes(29.0, &rule(15, RoundingMode::Down, 0)), 15);
+    assert_eq!(round_minutes(22.0, &rule(15, RoundingMode::Nearest, 0)), 15);
+    assert_eq!(round_minutes(23.0, &rule(15, RoundingMode::Nearest, 0)), 30);
+    assert_eq!(round_minutes(30.0, &rule(15, RoundingMode::Up, 0)), 30);
+  }
+
+  #[test]
+  fn rounding_without_increment_keeps_whole_minutes() {
+    assert_eq!(round_minutes(24.4, &rule(0, RoundingMode::Up, 0)), 24);
+    assert_eq!(round_minutes(24.6, &rule(0, RoundingMode::Down, 0)), 25);
+  }
+
+  #[test]
+  fn rounding_applies_the_minimum() {
+    assert_eq!(round_minutes(3.0, &rule(15, RoundingMode::Down, 10)), 10);
+    assert_eq!(round_minutes(0.0, &rule(0, RoundingMode::Up, 5)), 5);
+  }
+
+  #[test]
+  fn groups_by_project_and_day() {
+    let todos = [
+      todo("a", "Write", Some("Docs"), &[]),
+      todo("b", "Review", Some("Docs"), &[]),
+      todo("c", "Fix", Some("App"), &[]),
+    ];
+    let sessions = [
+      focus(Some("a"), 2, 25),
+      focus(Some("b"), 2, 25),
+      focus(Some("c"), 2, 20),
+      focus(Some("a"), 3, 25),
+      focus(None, 3, 10),
+      focus(Some("a"), 9, 25),
+    ];
+    let sheet = build(
+      date(2),
+      date(3),
+      TimesheetGrouping::Project,
+      &todos,
+      &sessions,
+      &rule(15, RoundingMode::Up, 0),
+    );
+    let rows: Vec<(&str, &str, u32, u32)> = sheet
+      .rows
+      .iter()
+      .map(|row| {
+        (
+          row.date.as_str(),
+          row.group.as_str(),
+          row.sessions,
+          row.billed_minutes,
+        )
+      })
+      .collect();
+    let unassigned = i18n::t!("timesheet.unassigned");
+    let mut expected = vec![
+      ("2026-03-02", "App", 1, 30),
+      ("2026-03-02", "Docs", 2, 60),
+      ("2026-03-03", "Docs", 1, 30),
+      ("2026-03-03", unassigned.as_str(), 1, 15),
+    ];
+    expected.sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.cmp(b.1)));
+    assert_eq!(rows, expected);
+    assert_eq!(sheet.total_sessions, 5);
+    assert_eq!(sheet.total_tracked_minutes, 105.0);
+    assert_eq!(sheet.total_billed_minutes, 135);
+  }
+
+  #[test]
+  fn groups_same_titled_todos_separately() {
+    let todos = [todo("a", "Sync", None, &[]), todo("b", "Sync", None, &[])];
+    let sessions = [focus(Some("a"), 2, 25), focus(Some("b"), 2, 25)];
+    let sheet = build(
+      date(2),
+      date(2),
+      TimesheetGrouping::Todo,
+      &todos,
+      &sessions,
+      &rule(0, RoundingMode::Up, 0),
+    );
+    assert_eq!(sheet.rows.len(), 2);
+    assert!(sheet.rows.iter().all(|row| row.group == "Sync"));
+  }
+
+  #[test]
+  fn splits_multi_tag_sessions_and_counts_them_once() {
+    let todos = [todo("a", "Write", None, &["writing", "client"])];
+    let sessions = [focus(Some("a"), 2, 30)];
+    let sheet = build(
+      date(2),
+      date(2),
+      TimesheetGrouping::Tag,
+      &todos,
+      &sessions,
+      &rule(0, RoundingMode::Up, 0),
+    );
+    assert_eq!(sheet.rows.len(), 2);
+    assert!(sheet.rows.iter().all(|row| row.tracked_minutes == 15.0));
+    assert_eq!(sheet.total_sessions, 1);
+    assert_eq!(sheet.total_tracked_minutes, 30.0);
+  }
+}
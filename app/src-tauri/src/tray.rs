@@ -1,25 +1,107 @@
+use chrono::Local;
+use parking_lot::Mutex;
 use tauri::{
-  menu::{MenuBuilder, MenuItemBuilder},
-  tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
-  AppHandle, Manager,
+  image::Image,
+  menu::{MenuBuilder, MenuItem, MenuItemBuilder, Submenu, SubmenuBuilder},
+  tray::{MouseButton, TrayIcon, TrayIconBuilder, TrayIconEvent},
+  AppHandle, Manager, Wry,
 };
 
+use crate::{
+  commands::{self, CommandError},
+  i18n,
+  state::AppState,
+  storage::{PomodoroSessionKind, TodoItem, TodoPriority},
+  timer::{TimerSnapshot, TimerStatus},
+  tray_icon, windows,
+};
+
+pub const TRAY_ID: &str = "main-tray";
+/// 托盘“待办”子菜单最多列出的未完成待办数量。
+const TRAY_TODO_LIMIT: usize = 8;
+const TRAY_TITLE_MAX_CHARS: usize = 40;
+
+#[derive(Clone)]
+struct TrayHandles {
+  status: MenuItem<Wry>,
+  todo: MenuItem<Wry>,
+  toggle_timer: MenuItem<Wry>,
+  skip: MenuItem<Wry>,
+  reset: MenuItem<Wry>,
+  main_window: MenuItem<Wry>,
+  floating_window: MenuItem<Wry>,
+  todos: Submenu<Wry>,
+  exit: MenuItem<Wry>,
+}
+
+/// 托盘菜单状态：保存可变菜单项的句柄，计时器或窗口变化时就地更新文字。
+#[derive(Default)]
+pub struct TrayController {
+  handles: Mutex<Option<TrayHandles>>,
+  linked_todo: Mutex<Option<(String, String)>>,
+  icon_key: Mutex<Option<IconKey>>,
+}
+
+/// 决定托盘图标外观的状态；只有它变化时才重新绘制，空闲时为 `None`。
+#[derive(Clone, PartialEq, Eq)]
+struct IconKey {
+  phase: PomodoroSessionKind,
+  status: TimerStatus,
+  step: u32,
+}
+
 pub fn init(app: &AppHandle) -> tauri::Result<()> {
-  let show_item = MenuItemBuilder::new("Show Main Window")
-    .id("show-main")
+  let status_item = MenuItemBuilder::new("FocusTodo")
+    .id("timer-status")
+    .enabled(false)
     .build(app)?;
+  let todo_item = MenuItemBuilder::new(i18n::t!("tray.task", title = i18n::t!("tray.taskNone")))
+    .id("timer-todo")
+    .enabled(false)
+    .build(app)?;
+  let toggle_item = MenuItemBuilder::new(i18n::t!("tray.startFocus"))
+    .id("timer-toggle")
+    .build(app)?;
+  let skip_item = MenuItemBuilder::new(i18n::t!("tray.skipPhase"))
+    .id("timer-skip")
+    .build(app)?;
+  let reset_item = MenuItemBuilder::new(i18n::t!("tray.resetTimer"))
+    .id("timer-reset")
+    .build(app)?;
+  let main_item = MenuItemBuilder::new(window_text(app, "main", i18n::t!("tray.mainWindow")))
+    .id("toggle-main")
+    .build(app)?;
+  let floating_item = MenuItemBuilder::new(window_text(
+    app,
+    "floating",
+    i18n::t!("tray.floatingWindow"),
+  ))
+  .id("toggle-floating")
+  .build(app)?;
 
-  let exit_item = MenuItemBuilder::new("Exit")
+  let todos_menu = SubmenuBuilder::with_id(app, "todos", i18n::t!("tray.todos")).build()?;
+
+  let exit_item = MenuItemBuilder::new(i18n::t!("tray.exit"))
     .id("exit-app")
     .build(app)?;
 
   let menu = MenuBuilder::new(app)
-    .item(&show_item)
+    .item(&status_item)
+    .item(&todo_item)
+    .separator()
+    .item(&todos_menu)
+    .separator()
+    .item(&toggle_item)
+    .item(&skip_item)
+    .item(&reset_item)
+    .separator()
+    .item(&main_item)
+    .item(&floating_item)
     .separator()
     .item(&exit_item)
     .build()?;
 
-  let mut builder = TrayIconBuilder::new()
+  let mut builder = TrayIconBuilder::with_id(TRAY_ID)
     .menu(&menu)
     .tooltip("FocusTodo");
 
@@ -38,27 +120,306 @@ pub fn init(app: &AppHandle) -> tauri::Result<()> {
           ..
         } | TrayIconEvent::DoubleClick { .. }
       ) {
-        if let Some(main) = tray.app_handle().get_webview_window("main") {
-          let _ = main.show();
-          let _ = main.set_focus();
+        if let Err(err) = windows::show_window(tray.app_handle(), "main") {
+          log::warn!("托盘唤起主窗口失败: {}", err);
         }
       }
     })
-    .on_menu_event(move |tray, event| {
-      let app = tray.app_handle();
-      match event.id.as_ref() {
-        "show-main" => {
-          if let Some(main) = app.get_webview_window("main") {
-            let _ = main.show();
-            let _ = main.set_focus();
-          }
-        }
-        "exit-app" => app.exit(0),
-        _ => {}
+    .on_menu_event(move |app, event| {
+      if let Err(err) = handle_menu(app, event.id.as_ref()) {
+        log::warn!("托盘菜单操作 {} 失败: {}", event.id.as_ref(), err);
       }
     })
     .build(&app_handle)?;
 
+  *app.state::<AppState>().tray().handles.lock() = Some(TrayHandles {
+    status: status_item,
+    todo: todo_item,
+    toggle_timer: toggle_item,
+    skip: skip_item,
+    reset: reset_item,
+    main_window: main_item,
+    floating_window: floating_item,
+    todos: todos_menu,
+    exit: exit_item,
+  });
+  refresh_todos(app);
   Ok(())
 }
 
+fn handle_menu(app: &AppHandle, id: &str) -> Result<(), CommandError> {
+  match id {
+    "timer-toggle" => commands::timer::toggle_timer(app.clone(), app.state()).map(|_| ()),
+    "timer-skip" => commands::timer::skip_phase(app.clone(), app.state()).map(|_| ()),
+    "timer-reset" => commands::timer::reset_timer(app.clone(), app.state(), None).map(|_| ()),
+    "toggle-main" => windows::toggle_window(app, "main")
+      .map(|_| ())
+      .map_err(CommandError::from),
+    "toggle-floating" => windows::toggle_window(app, "floating")
+      .map(|_| ())
+      .map_err(CommandError::from),
+    "exit-app" => {
+      app.exit(0);
+      Ok(())
+    }
+    other => {
+      if let Some(todo_id) = other.strip_prefix("todo-complete:") {
+        commands::todo::toggle_complete(app.clone(), app.state(), todo_id.to_string(), true)
+          .map(|_| ())
+      } else if let Some(todo_id) = other.strip_prefix("todo-focus:") {
+        commands::timer::start_timer(
+          app.clone(),
+          app.state(),
+          Some(PomodoroSessionKind::Focus),
+          Some(todo_id.to_string()),
+        )
+        .map(|_| ())
+      } else {
+        Ok(())
+      }
+    }
+  }
+}
+
+/// 待办变化后重建托盘中的待办子菜单，并刷新关联待办的标题。
+pub fn refresh_todos(app: &AppHandle) {
+  let state = app.state::<AppState>();
+  state.tray().linked_todo.lock().take();
+  if let Err(err) = state.tray().rebuild_todos(app, &state) {
+    log::warn!("重建托盘待办菜单失败: {}", err);
+  }
+  refresh(app);
+}
+
+/// 切换语言后重新设置托盘中不随状态变化的菜单文字，并重建待办子菜单。
+pub fn retranslate(app: &AppHandle) {
+  let state = app.state::<AppState>();
+  if let Err(err) = state.tray().relabel() {
+    log::warn!("更新托盘菜单语言失败: {}", err);
+  }
+  refresh_todos(app);
+}
+
+/// 按当前计时器与窗口状态刷新托盘菜单文字、提示与标题。
+pub fn refresh(app: &AppHandle) {
+  let state = app.state::<AppState>();
+  let snapshot = state.timer().snapshot();
+  if let Err(err) = state.tray().update(app, &state, &snapshot) {
+    log::warn!("刷新托盘状态失败: {}", err);
+  }
+}
+
+impl TrayController {
+  fn relabel(&self) -> tauri::Result<()> {
+    let Some(handles) = self.handles.lock().clone() else {
+      return Ok(());
+    };
+    handles.skip.set_text(i18n::t!("tray.skipPhase"))?;
+    handles.reset.set_text(i18n::t!("tray.resetTimer"))?;
+    handles.todos.set_text(i18n::t!("tray.todos"))?;
+    handles.exit.set_text(i18n::t!("tray.exit"))
+  }
+
+  fn update(
+    &self,
+    app: &AppHandle,
+    state: &AppState,
+    snapshot: &TimerSnapshot,
+  ) -> tauri::Result<()> {
+    // 菜单操作会切到主线程执行，先复制句柄再释放锁，避免与菜单回调互相等待。
+    let Some(handles) = self.handles.lock().clone() else {
+      return Ok(());
+    };
+    let status = status_text(snapshot);
+    let todo_title = self.linked_todo_title(state, snapshot.todo_id.as_deref());
+
+    handles.status.set_text(&status)?;
+    let task = todo_title
+      .clone()
+      .unwrap_or_else(|| i18n::t!("tray.taskNone"));
+    handles.todo.set_text(i18n::t!("tray.task", title = task))?;
+    handles.toggle_timer.set_text(toggle_text(snapshot))?;
+    handles
+      .main_window
+      .set_text(window_text(app, "main", i18n::t!("tray.mainWindow")))?;
+    handles.floating_window.set_text(window_text(
+      app,
+      "floating",
+      i18n::t!("tray.floatingWindow"),
+    ))?;
+
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+      let tooltip = match &todo_title {
+        Some(title) => format!("FocusTodo - {status}\n{title}"),
+        None => format!("FocusTodo - {status}"),
+      };
+      tray.set_tooltip(Some(tooltip))?;
+      let title =
+        (snapshot.status != TimerStatus::Idle).then(|| format_clock(snapshot.remaining_seconds));
+      tray.set_title(title)?;
+      self.update_icon(app, &tray, snapshot)?;
+    }
+    Ok(())
+  }
+
+  fn rebuild_todos(&self, app: &AppHandle, state: &AppState) -> tauri::Result<()> {
+    let Some(handles) = self.handles.lock().clone() else {
+      return Ok(());
+    };
+    let todos = state.store().load_todos().unwrap_or_default();
+    let top = top_todos(&todos, TRAY_TODO_LIMIT);
+
+    while handles.todos.remove_at(0)?.is_some() {}
+    if top.is_empty() {
+      let empty = MenuItemBuilder::new(i18n::t!("tray.noOpenTodos"))
+        .enabled(false)
+        .build(app)?;
+      handles.todos.append(&empty)?;
+      return Ok(());
+    }
+    for todo in top {
+      let complete = MenuItemBuilder::new(i18n::t!("tray.markComplete"))
+        .id(format!("todo-complete:{}", todo.id))
+        .build(app)?;
+      let focus = MenuItemBuilder::new(i18n::t!("tray.startFocus"))
+        .id(format!("todo-focus:{}", todo.id))
+        .build(app)?;
+      let entry = SubmenuBuilder::new(app, todo_label(todo))
+        .item(&focus)
+        .item(&complete)
+        .build()?;
+      handles.todos.append(&entry)?;
+    }
+    Ok(())
+  }
+
+  fn update_icon(
+    &self,
+    app: &AppHandle,
+    tray: &TrayIcon,
+    snapshot: &TimerSnapshot,
+  ) -> tauri::Result<()> {
+    let key = (snapshot.status != TimerStatus::Idle).then(|| IconKey {
+      phase: snapshot.phase.clone(),
+      status: snapshot.status,
+      step: tray_icon::ring_step(snapshot.remaining_seconds, snapshot.duration_seconds),
+    });
+    {
+      let mut last = self.icon_key.lock();
+      if *last == key {
+        return Ok(());
+      }
+      *last = key.clone();
+    }
+    match key {
+      Some(key) => {
+        let rgba = tray_icon::render(tray_icon::ICON_SIZE, key.step, &key.phase, key.status);
+        tray.set_icon(Some(Image::new_owned(
+          rgba,
+          tray_icon::ICON_SIZE,
+          tray_icon::ICON_SIZE,
+        )))
+      }
+      None => tray.set_icon(app.default_window_icon().cloned()),
+    }
+  }
+
+  /// 关联待办的标题只在待办变化时重新读取，避免每秒读盘。
+  fn linked_todo_title(&self, state: &AppState, todo_id: Option<&str>) -> Option<String> {
+    let todo_id = todo_id?;
+    let mut cached = self.linked_todo.lock();
+    if let Some((id, title)) = cached.as_ref() {
+      if id == todo_id {
+        return Some(title.clone());
+      }
+    }
+    let title = state
+      .store()
+      .load_todos()
+      .ok()?
+      .into_iter()
+      .find(|todo| todo.id == todo_id)?
+      .title;
+    *cached = Some((todo_id.to_string(), title.clone()));
+    Some(title)
+  }
+}
+
+/// 未完成待办按优先级、截止时间、创建时间排序后取前若干项。
+fn top_todos(todos: &[TodoItem], limit: usize) -> Vec<&TodoItem> {
+  let mut open: Vec<&TodoItem> = todos.iter().filter(|todo| !todo.completed).collect();
+  open.sort_by(|a, b| {
+    a.priority
+      .rank()
+      .cmp(&b.priority.rank())
+      .then_with(|| match (a.due_time(), b.due_time()) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+      })
+      .then_with(|| a.created_at.cmp(&b.created_at))
+  });
+  open.truncate(limit);
+  open
+}
+
+fn todo_label(todo: &TodoItem) -> String {
+  let mut title: String = todo.title.chars().take(TRAY_TITLE_MAX_CHARS).collect();
+  if todo.title.chars().count() > TRAY_TITLE_MAX_CHARS {
+    title.push('…');
+  }
+  if todo.priority == TodoPriority::High {
+    title = format!("! {title}");
+  }
+  match todo.due_time() {
+    Some(due) => i18n::t!(
+      "tray.todoDue",
+      title = title,
+      due = due.with_timezone(&Local).format("%m-%d %H:%M")
+    ),
+    None => title,
+  }
+}
+
+fn phase_label(phase: &PomodoroSessionKind) -> String {
+  match phase {
+    PomodoroSessionKind::Focus => i18n::t!("phase.focus"),
+    PomodoroSessionKind::ShortBreak => i18n::t!("phase.shortBreak"),
+    PomodoroSessionKind::LongBreak => i18n::t!("phase.longBreak"),
+  }
+}
+
+fn status_text(snapshot: &TimerSnapshot) -> String {
+  let phase = phase_label(&snapshot.phase);
+  let clock = format_clock(snapshot.remaining_seconds);
+  match snapshot.status {
+    TimerStatus::Running => i18n::t!("tray.statusRunning", phase = phase, clock = clock),
+    TimerStatus::Paused => i18n::t!("tray.statusPaused", phase = phase, clock = clock),
+    TimerStatus::Idle => i18n::t!("tray.statusIdle", phase = phase, clock = clock),
+  }
+}
+
+fn toggle_text(snapshot: &TimerSnapshot) -> String {
+  match snapshot.status {
+    TimerStatus::Running => i18n::t!("tray.pause"),
+    TimerStatus::Paused => i18n::t!("tray.resume"),
+    TimerStatus::Idle => i18n::t!("tray.start", phase = phase_label(&snapshot.phase)),
+  }
+}
+
+fn window_text(app: &AppHandle, label: &str, name: String) -> String {
+  let visible = app
+    .get_webview_window(label)
+    .and_then(|window| window.is_visible().ok())
+    .unwrap_or(false);
+  if visible {
+    i18n::t!("tray.hideWindow", name = name)
+  } else {
+    i18n::t!("tray.showWindow", name = name)
+  }
+}
+
+fn format_clock(seconds: u64) -> String {
+  format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,203 @@
+use crate::{storage::PomodoroSessionKind, timer::TimerStatus};
+
+/// 托盘图标边长（像素），系统会按需缩放。
+pub const ICON_SIZE: u32 = 32;
+/// 进度环的离散步数，步数变化时才重新生成图标。
+pub const RING_STEPS: u32 = 60;
+
+const SUPERSAMPLE: u32 = 4;
+const RING_WIDTH_RATIO: f32 = 0.22;
+const TRACK_COLOR: Rgba = [128, 128, 128, 90];
+
+pub type Rgba = [u8; 4];
+
+pub fn phase_color(phase: &PomodoroSessionKind) -> Rgba {
+  match phase {
+    PomodoroSessionKind::Focus => [229, 72, 77, 255],
+    PomodoroSessionKind::ShortBreak => [48, 164, 108, 255],
+    PomodoroSessionKind::LongBreak => [62, 99, 221, 255],
+  }
+}
+
+/// 把剩余时间换算为 0..=RING_STEPS 的步数，阶段刚开始时为满环。
+pub fn ring_step(remaining_seconds: u64, duration_seconds: u64) -> u32 {
+  if duration_seconds == 0 {
+    return 0;
+  }
+  let ratio = remaining_seconds.min(duration_seconds) as f64 / duration_seconds as f64;
+  (ratio * f64::from(RING_STEPS)).ceil() as u32
+}
+
+/// 绘制计时器进度环，返回 `size * size * 4` 字节的 RGBA 像素。
+///
+/// 进度从 12 点方向顺时针绘制；暂停时前景色减半透明度以示区分。
+pub fn render(size: u32, step: u32, phase: &PomodoroSessionKind, status: TimerStatus) -> Vec<u8> {
+  let mut color = phase_color(phase);
+  if status == TimerStatus::Paused {
+    color[3] /= 2;
+  }
+  let progress = step.min(RING_STEPS) as f32 / RING_STEPS as f32;
+  render_ring(size, progress, color)
+}
+
+/// 纯像素渲染：以超采样计算每个像素被圆环覆盖的比例，实现抗锯齿。
+pub fn render_ring(size: u32, progress: f32, color: Rgba) -> Vec<u8> {
+  let mut pixels = vec![0u8; (size * size * 4) as usize];
+  if size == 0 {
+    return pixels;
+  }
+  let center = size as f32 / 2.0;
+  let outer = center - 1.0;
+  let inner = outer - size as f32 * RING_WIDTH_RATIO;
+  let progress = progress.clamp(0.0, 1.0);
+  let samples = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+
+  for y in 0..size {
+    for x in 0..size {
+      let mut track_hits = 0u32;
+      let mut fill_hits = 0u32;
+      for sy in 0..SUPERSAMPLE {
+        for sx in 0..SUPERSAMPLE {
+          let px = x as f32 + (sx as f32 + 0.5) / SUPERSAMPLE as f32 - center;
+          let py = y as f32 + (sy as f32 + 0.5) / SUPERSAMPLE as f32 - center;
+          let distance = (px * px + py * py).sqrt();
+          if distance < inner || distance > outer {
+            continue;
+          }
+          if angle_fraction(px, py) < progress {
+            fill_hits += 1;
+          } else {
+            track_hits += 1;
+          }
+        }
+      }
+      if fill_hits == 0 && track_hits == 0 {
+        continue;
+      }
+      let fill = with_coverage(color, fill_hits as f32 / samples);
+      let track = with_coverage(TRACK_COLOR, track_hits as f32 / samples);
+      let offset = ((y * size + x) * 4) as usize;
+      pixels[offset..offset + 4].copy_from_slice(&blend(fill, track));
+    }
+  }
+  pixels
+}
+
+/// 以 12 点方向为 0，顺时针返回 0..1 的角度比例（屏幕坐标 y 轴向下）。
+fn angle_fraction(x: f32, y: f32) -> f32 {
+  let angle = x.atan2(-y);
+  let turn = std::f32::consts::TAU;
+  ((angle + turn) % turn) / turn
+}
+
+fn with_coverage(color: Rgba, coverage: f32) -> Rgba {
+  [
+    color[0],
+    color[1],
+    color[2],
+    (f32::from(color[3]) * coverage).round() as u8,
+  ]
+}
+
+/// 非预乘 alpha 的 source-over 合成：`top` 覆盖在 `bottom` 之上。
+fn blend(top: Rgba, bottom: Rgba) -> Rgba {
+  let top_alpha = f32::from(top[3]) / 255.0;
+  let bottom_alpha = f32::from(bottom[3]) / 255.0;
+  let alpha = top_alpha + bottom_alpha * (1.0 - top_alpha);
+  if alpha <= 0.0 {
+    return [0, 0, 0, 0];
+  }
+  let channel = |index: usize| {
+    let value = (f32::from(top[index]) * top_alpha
+      + f32::from(bottom[index]) * bottom_alpha * (1.0 - top_alpha))
+      / alpha;
+    value.round().clamp(0.0, 255.0) as u8
+  };
+  [
+    channel(0),
+    channel(1),
+    channel(2),
+    (alpha * 255.0).round() as u8,
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const SIZE: u32 = ICON_SIZE;
+  const RED: Rgba = [229, 72, 77, 255];
+
+  fn pixel(pixels: &[u8], x: u32, y: u32) -> Rgba {
+    let offset = ((y * SIZE + x) * 4) as usize;
+    pixels[offset..offset + 4].try_into().unwrap()
+  }
+
+  #[test]
+  fn ring_step_bounds() {
+    assert_eq!(ring_step(0, 1500), 0);
+    assert_eq!(ring_step(1500, 1500), RING_STEPS);
+    assert_eq!(ring_step(3000, 1500), RING_STEPS);
+    assert_eq!(ring_step(1, 1500), 1);
+    assert_eq!(ring_step(0, 0), 0);
+    assert_eq!(ring_step(60, 0), 0);
+  }
+
+  #[test]
+  fn render_ring_has_expected_size() {
+    assert_eq!(
+      render_ring(SIZE, 0.5, RED).len(),
+      (SIZE * SIZE * 4) as usize
+    );
+    assert!(render_ring(0, 0.5, RED).is_empty());
+  }
+
+  #[test]
+  fn full_ring_uses_phase_color_at_twelve_o_clock() {
+    let pixels = render_ring(SIZE, 1.0, RED);
+    assert_eq!(pixel(&pixels, SIZE / 2, 4), RED);
+  }
+
+  #[test]
+  fn empty_ring_shows_only_the_track() {
+    let pixels = render_ring(SIZE, 0.0, RED);
+    assert_eq!(pixel(&pixels, SIZE / 2, 4), TRACK_COLOR);
+  }
+
+  #[test]
+  fn center_and_corners_are_transparent() {
+    let pixels = render_ring(SIZE, 1.0, RED);
+    for (x, y) in [
+      (SIZE / 2, SIZE / 2),
+      (0, 0),
+      (SIZE - 1, 0),
+      (0, SIZE - 1),
+      (SIZE - 1, SIZE - 1),
+    ] {
+      assert_eq!(pixel(&pixels, x, y)[3], 0, "pixel ({x}, {y})");
+    }
+  }
+
+  #[test]
+  fn progress_changes_the_pixels() {
+    assert_ne!(render_ring(SIZE, 0.0, RED), render_ring(SIZE, 1.0, RED));
+  }
+
+  #[test]
+  fn paused_timer_halves_foreground_alpha() {
+    let running = render(
+      SIZE,
+      RING_STEPS,
+      &PomodoroSessionKind::Focus,
+      TimerStatus::Running,
+    );
+    let paused = render(
+      SIZE,
+      RING_STEPS,
+      &PomodoroSessionKind::Focus,
+      TimerStatus::Paused,
+    );
+    assert_eq!(pixel(&running, SIZE / 2, 4)[3], 255);
+    assert_eq!(pixel(&paused, SIZE / 2, 4)[3], 127);
+  }
+}
@@ -0,0 +1,77 @@
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::WindowKind;
+use crate::{
+  i18n,
+  state::AppState,
+  storage::{StorageError, UserSettings},
+};
+
+/// 通知悬浮窗类窗口的前端按设置调整透明度等仅能在网页侧生效的表现。
+pub const BEHAVIOR_EVENT: &str = "window:behavior";
+
+const MIN_OPACITY: f32 = 0.2;
+const MAX_OPACITY: f32 = 1.0;
+const MAX_SNAP_THRESHOLD: u32 = 200;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BehaviorPayload {
+  always_on_top: bool,
+  visible_on_all_workspaces: bool,
+  opacity: f32,
+  show_completed: bool,
+}
+
+pub fn validate_behavior(settings: &UserSettings) -> Result<(), StorageError> {
+  if !(MIN_OPACITY..=MAX_OPACITY).contains(&settings.floating_opacity) {
+    return Err(StorageError::invalid_field(
+      "floatingOpacity",
+      i18n::t!(
+        "window.invalidOpacity",
+        min = MIN_OPACITY,
+        max = MAX_OPACITY
+      ),
+    ));
+  }
+  if settings.snap_threshold > MAX_SNAP_THRESHOLD {
+    return Err(StorageError::invalid_field(
+      "snapThreshold",
+      i18n::t!("window.invalidSnapThreshold", max = MAX_SNAP_THRESHOLD),
+    ));
+  }
+  Ok(())
+}
+
+/// 把置顶、全部桌面可见与透明度设置应用到所有打开的悬浮窗（含项目悬浮窗）；
+/// 窗口单独保存的偏好优先于全局设置。
+pub fn apply_behavior(app: &AppHandle, settings: &UserSettings) -> tauri::Result<()> {
+  let state = app.state::<AppState>();
+  for label in state.windows().labels(WindowKind::is_floating) {
+    let Some(window) = app.get_webview_window(&label) else {
+      continue;
+    };
+    let preference = settings.window_preferences.get(&label);
+    let always_on_top = preference
+      .and_then(|preference| preference.always_on_top)
+      .unwrap_or(settings.always_on_top);
+    let visible_on_all_workspaces = preference
+      .and_then(|preference| preference.visible_on_all_workspaces)
+      .unwrap_or(settings.visible_on_all_workspaces);
+    window.set_always_on_top(always_on_top)?;
+    window.set_visible_on_all_workspaces(visible_on_all_workspaces)?;
+    // 窗口本身没有跨平台的透明度接口，由透明窗口内的页面自行调整。
+    app.emit_to(
+      label.as_str(),
+      BEHAVIOR_EVENT,
+      BehaviorPayload {
+        always_on_top,
+        visible_on_all_workspaces,
+        opacity: settings.floating_opacity,
+        show_completed: settings.show_completed_in_floating,
+      },
+    )?;
+  }
+  Ok(())
+}
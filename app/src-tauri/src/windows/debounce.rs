@@ -0,0 +1,59 @@
+use std::{
+  sync::Arc,
+  thread,
+  time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+type Task = Box<dyn FnOnce() + Send>;
+
+struct Pending {
+  deadline: Instant,
+  task: Task,
+}
+
+/// 合并连续触发的窗口事件：只在最后一次调用后静默 `delay` 才执行最新的任务。
+#[derive(Default)]
+pub struct Debouncer {
+  pending: Mutex<Option<Pending>>,
+  worker_running: Mutex<bool>,
+}
+
+impl Debouncer {
+  pub fn schedule(self: &Arc<Self>, delay: Duration, task: impl FnOnce() + Send + 'static) {
+    *self.pending.lock() = Some(Pending {
+      deadline: Instant::now() + delay,
+      task: Box::new(task),
+    });
+
+    let mut running = self.worker_running.lock();
+    if *running {
+      return;
+    }
+    *running = true;
+    let debouncer = Arc::clone(self);
+    thread::spawn(move || loop {
+      thread::sleep(POLL_INTERVAL);
+      let due = {
+        let mut pending = debouncer.pending.lock();
+        match pending.as_ref() {
+          Some(next) if next.deadline <= Instant::now() => pending.take(),
+          Some(_) => continue,
+          None => None,
+        }
+      };
+      if let Some(next) = due {
+        (next.task)();
+      }
+      // 执行期间可能又有新任务进来，交给同一个线程继续等待。
+      let mut running = debouncer.worker_running.lock();
+      if debouncer.pending.lock().is_none() {
+        *running = false;
+        break;
+      }
+    });
+  }
+}
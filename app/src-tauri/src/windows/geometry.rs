@@ -0,0 +1,328 @@
+use tauri::{Monitor, WebviewWindow};
+
+use crate::storage::WindowGeometry;
+
+/// 窗口至少要有这么多像素落在某个显示器内，才认为仍然可见。
+const MIN_VISIBLE: i32 = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+  pub x: i32,
+  pub y: i32,
+  pub width: i32,
+  pub height: i32,
+}
+
+impl Rect {
+  pub fn right(&self) -> i32 {
+    self.x + self.width
+  }
+
+  pub fn bottom(&self) -> i32 {
+    self.y + self.height
+  }
+
+  pub fn contains(&self, x: f64, y: f64) -> bool {
+    x >= f64::from(self.x)
+      && x < f64::from(self.right())
+      && y >= f64::from(self.y)
+      && y < f64::from(self.bottom())
+  }
+
+  /// 与另一个矩形的交集，不相交时宽高为 0。
+  pub fn intersection(&self, other: Rect) -> Rect {
+    let x = self.x.max(other.x);
+    let y = self.y.max(other.y);
+    Rect {
+      x,
+      y,
+      width: (self.right().min(other.right()) - x).max(0),
+      height: (self.bottom().min(other.bottom()) - y).max(0),
+    }
+  }
+
+  /// 宽或高不为正；部分系统会为断开中的显示器报告这样的工作区。
+  fn is_empty(&self) -> bool {
+    self.width <= 0 || self.height <= 0
+  }
+
+  fn area(&self) -> i64 {
+    i64::from(self.width) * i64::from(self.height)
+  }
+}
+
+/// 显示器的名称、可用工作区（物理像素）与缩放比例。
+#[derive(Debug, Clone)]
+pub struct MonitorArea {
+  pub name: Option<String>,
+  pub work_area: Rect,
+  pub scale_factor: f64,
+}
+
+impl MonitorArea {
+  pub fn from_monitor(monitor: &Monitor) -> Self {
+    let work_area = monitor.work_area();
+    Self {
+      name: monitor.name().cloned(),
+      work_area: Rect {
+        x: work_area.position.x,
+        y: work_area.position.y,
+        width: work_area.size.width as i32,
+        height: work_area.size.height as i32,
+      },
+      scale_factor: monitor.scale_factor(),
+    }
+  }
+}
+
+/// 把保存的窗口矩形放回当前可用的显示器上。
+///
+/// 仍然可见时只夹取到工作区内；否则移到同名显示器（找不到时为主显示器）的中央。
+/// 缩放比例与保存时不同时按比例换算尺寸。
+pub fn resolve(
+  saved: Rect,
+  saved_monitor: Option<&str>,
+  saved_scale: Option<f64>,
+  monitors: &[MonitorArea],
+  primary: Option<usize>,
+) -> Option<Rect> {
+  let visible_on = monitors
+    .iter()
+    .enumerate()
+    .map(|(index, monitor)| (index, saved.intersection(monitor.work_area)))
+    .filter(|(_, overlap)| overlap.width >= MIN_VISIBLE && overlap.height >= MIN_VISIBLE)
+    .max_by_key(|(_, overlap)| overlap.area())
+    .map(|(index, _)| index);
+  let by_name = saved_monitor.and_then(|name| {
+    monitors
+      .iter()
+      .position(|monitor| monitor.name.as_deref() == Some(name))
+  });
+
+  // 仍然可见就留在原处，只有完全看不到时才按显示器名称或主显示器重新安置。
+  let (target, keep_position) = match (visible_on, by_name) {
+    (Some(visible), _) => (visible, true),
+    (None, Some(named)) => (named, false),
+    (None, None) => (
+      primary.filter(|index| *index < monitors.len()).unwrap_or(0),
+      false,
+    ),
+  };
+  let monitor = monitors.get(target)?;
+  let area = monitor.work_area;
+  // 工作区宽高不为正时夹取范围会倒置，保留保存的矩形。
+  if area.is_empty() {
+    return Some(saved);
+  }
+
+  let ratio = match saved_scale {
+    Some(scale) if scale > 0.0 => monitor.scale_factor / scale,
+    _ => 1.0,
+  };
+  let width = ((f64::from(saved.width) * ratio).round() as i32).clamp(1, area.width.max(1));
+  let height = ((f64::from(saved.height) * ratio).round() as i32).clamp(1, area.height.max(1));
+
+  let (x, y) = if keep_position {
+    (saved.x, saved.y)
+  } else {
+    (
+      area.x + (area.width - width) / 2,
+      area.y + (area.height - height) / 2,
+    )
+  };
+  Some(Rect {
+    x: x.clamp(area.x, area.right() - width),
+    y: y.clamp(area.y, area.bottom() - height),
+    width,
+    height,
+  })
+}
+
+/// 改变窗口尺寸时保持离工作区角落最近的那个角不动，再夹取到工作区内。
+///
+/// 例如贴在右下角的窗口缩小后仍贴在右下角，不会因为以左上角为基准而跳开。
+pub fn anchor_resize(current: Rect, area: Rect, width: i32, height: i32) -> Rect {
+  // 工作区宽高不为正时夹取范围会倒置，只改尺寸并保持左上角不动。
+  if area.is_empty() {
+    return Rect {
+      width: width.max(1),
+      height: height.max(1),
+      ..current
+    };
+  }
+  let width = width.clamp(1, area.width.max(1));
+  let height = height.clamp(1, area.height.max(1));
+  let anchor_right = (area.right() - current.right()) < (current.x - area.x);
+  let anchor_bottom = (area.bottom() - current.bottom()) < (current.y - area.y);
+  let x = if anchor_right {
+    current.right() - width
+  } else {
+    current.x
+  };
+  let y = if anchor_bottom {
+    current.bottom() - height
+  } else {
+    current.y
+  };
+  Rect {
+    x: x.clamp(area.x, area.right() - width),
+    y: y.clamp(area.y, area.bottom() - height),
+    width,
+    height,
+  }
+}
+
+/// 为即将保存的几何信息补上窗口当前所在显示器的名称与缩放比例。
+pub fn annotate_geometry(window: &WebviewWindow, geometry: &mut WindowGeometry) {
+  let monitor = match (geometry.x, geometry.y) {
+    (Some(x), Some(y)) => window
+      .monitor_from_point(f64::from(x), f64::from(y))
+      .ok()
+      .flatten(),
+    _ => None,
+  }
+  .or_else(|| window.current_monitor().ok().flatten());
+  if let Some(monitor) = monitor {
+    geometry.monitor = monitor.name().cloned();
+    geometry.scale_factor = Some(monitor.scale_factor());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn rect(x: i32, y: i32, width: i32, height: i32) -> Rect {
+    Rect {
+      x,
+      y,
+      width,
+      height,
+    }
+  }
+
+  fn monitor(name: &str, work_area: Rect, scale_factor: f64) -> MonitorArea {
+    MonitorArea {
+      name: Some(name.to_string()),
+      work_area,
+      scale_factor,
+    }
+  }
+
+  fn monitors() -> Vec<MonitorArea> {
+    vec![
+      monitor("Built-in", rect(0, 0, 1920, 1040), 1.0),
+      monitor("External", rect(1920, 0, 2560, 1400), 1.0),
+    ]
+  }
+
+  #[test]
+  fn disconnected_monitor_moves_to_primary_center() {
+    let saved = rect(5000, 100, 800, 600);
+    assert_eq!(
+      resolve(saved, Some("Unplugged"), Some(1.0), &monitors(), Some(0)),
+      Some(rect(560, 220, 800, 600))
+    );
+  }
+
+  #[test]
+  fn offscreen_rect_moves_to_monitor_with_same_name() {
+    let saved = rect(-4000, -4000, 800, 600);
+    assert_eq!(
+      resolve(saved, Some("External"), Some(1.0), &monitors(), Some(0)),
+      Some(rect(2800, 400, 800, 600))
+    );
+  }
+
+  #[test]
+  fn partially_visible_rect_is_clamped_in_place() {
+    let saved = rect(1800, 100, 400, 300);
+    assert_eq!(
+      resolve(
+        saved,
+        Some("Built-in"),
+        Some(1.0),
+        &monitors()[..1],
+        Some(0)
+      ),
+      Some(rect(1520, 100, 400, 300))
+    );
+    // 跨两个显示器时留在重叠面积更大的那个上。
+    assert_eq!(
+      resolve(saved, Some("Built-in"), Some(1.0), &monitors(), Some(0)),
+      Some(rect(1920, 100, 400, 300))
+    );
+  }
+
+  #[test]
+  fn rect_larger_than_work_area_is_shrunk() {
+    let saved = rect(-50, -50, 2500, 1500);
+    assert_eq!(
+      resolve(
+        saved,
+        Some("Built-in"),
+        Some(1.0),
+        &monitors()[..1],
+        Some(0)
+      ),
+      Some(rect(0, 0, 1920, 1040))
+    );
+  }
+
+  #[test]
+  fn scale_change_converts_size() {
+    let monitors = [monitor("Retina", rect(0, 0, 3840, 2160), 2.0)];
+    assert_eq!(
+      resolve(
+        rect(100, 100, 800, 600),
+        Some("Retina"),
+        Some(1.0),
+        &monitors,
+        None
+      ),
+      Some(rect(100, 100, 1600, 1200))
+    );
+  }
+
+  #[test]
+  fn resolve_without_monitors_or_with_empty_work_area() {
+    let saved = rect(100, 100, 800, 600);
+    assert_eq!(resolve(saved, None, None, &[], None), None);
+    let empty = [monitor("Sleeping", rect(0, 0, 0, 0), 1.0)];
+    assert_eq!(resolve(saved, None, None, &empty, None), Some(saved));
+  }
+
+  #[test]
+  fn anchor_resize_keeps_nearest_corner() {
+    let area = rect(0, 0, 1920, 1040);
+    let cases = [
+      ("左上", rect(10, 10, 400, 300), rect(10, 10, 200, 100)),
+      ("右上", rect(1510, 10, 400, 300), rect(1710, 10, 200, 100)),
+      ("左下", rect(10, 730, 400, 300), rect(10, 930, 200, 100)),
+      ("右下", rect(1510, 730, 400, 300), rect(1710, 930, 200, 100)),
+    ];
+    for (name, current, expected) in cases {
+      assert_eq!(anchor_resize(current, area, 200, 100), expected, "{name}");
+    }
+  }
+
+  #[test]
+  fn anchor_resize_clamps_to_work_area() {
+    let area = rect(1920, 0, 2560, 1400);
+    // 贴在右下角放大时向左上生长，仍留在工作区内。
+    assert_eq!(
+      anchor_resize(rect(4080, 1100, 400, 300), area, 600, 400),
+      rect(3880, 1000, 600, 400)
+    );
+    // 比工作区还大时缩到工作区大小。
+    assert_eq!(
+      anchor_resize(rect(2000, 100, 400, 300), area, 3000, 2000),
+      rect(1920, 0, 2560, 1400)
+    );
+    // 工作区为空时只改尺寸。
+    assert_eq!(
+      anchor_resize(rect(10, 20, 400, 300), rect(0, 0, 0, 0), 200, 0),
+      rect(10, 20, 200, 1)
+    );
+  }
+}
@@ -0,0 +1,248 @@
+use tauri::{
+  App, AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewWindow, WindowEvent,
+};
+
+use crate::{
+  autostart,
+  state::AppState,
+  storage::{CloseBehavior, WindowGeometry},
+  tray,
+};
+
+mod behavior;
+mod debounce;
+mod geometry;
+mod mode;
+mod persist;
+mod registry;
+mod snap;
+
+pub use behavior::{apply_behavior, validate_behavior};
+pub use geometry::annotate_geometry;
+pub use mode::{geometry_key, set_floating_mode};
+pub use registry::{
+  apply_preference, close_window, focus_window, open_window, WindowKind, WindowRegistry,
+};
+use geometry::{MonitorArea, Rect};
+
+pub fn init(app: &App) -> tauri::Result<()> {
+  log::debug!("窗口初始化开始");
+
+  let settings = app
+    .state::<AppState>()
+    .store()
+    .load_settings()
+    .unwrap_or_default();
+
+  log::debug!("已加载窗口状态: {:?}", settings.window_state);
+
+  if let Some(main_window) = app.get_webview_window("main") {
+    // 确保窗口先显示，避免在屏幕外无法看到；设置为启动时隐藏则只留在托盘
+    if autostart::start_hidden(&settings) {
+      log::info!("启动时隐藏主窗口，仅显示托盘图标");
+    } else if let Err(e) = main_window.show() {
+      log::error!("主窗口显示失败: {}", e);
+    }
+
+    // 应用保存的窗口几何信息（如果有的话）
+    let main_geometry = settings.window_state.get("main");
+    if let Err(e) = apply_geometry(&main_window, &main_geometry) {
+      log::warn!("无法应用主窗口几何信息: {}", e);
+      // 如果应用几何信息失败，尝试居中显示
+      if let Err(e) = main_window.center() {
+        log::warn!("主窗口居中失败: {}", e);
+      }
+    }
+    if main_geometry.maximized {
+      if let Err(e) = main_window.maximize() {
+        log::warn!("主窗口最大化失败: {}", e);
+      }
+    }
+
+    let main_window_for_event = main_window.clone();
+    main_window.on_window_event(move |event| {
+      if let WindowEvent::CloseRequested { api, .. } = event {
+        let behavior = main_window_for_event
+          .state::<AppState>()
+          .store()
+          .load_settings()
+          .map(|settings| settings.close_behavior)
+          .unwrap_or_default();
+        log::debug!("主窗口关闭请求，按设置处理: {:?}", behavior);
+        if behavior == CloseBehavior::Quit {
+          main_window_for_event.app_handle().exit(0);
+          return;
+        }
+        api.prevent_close();
+
+        let result = match behavior {
+          CloseBehavior::Minimize => main_window_for_event.minimize(),
+          _ => main_window_for_event.hide(),
+        };
+        if let Err(err) = result {
+          log::warn!("隐藏主窗口失败: {}", err);
+        }
+        persist::schedule(&main_window_for_event);
+        tray::refresh(main_window_for_event.app_handle());
+      }
+    });
+  } else {
+    log::error!("未找到主窗口");
+  }
+
+  if let Some(floating_window) = app.get_webview_window("floating") {
+    let floating_geometry = settings
+      .window_state
+      .get(&geometry_key("floating", &settings));
+    if let Err(e) = mode::apply_constraints(&floating_window, settings.floating_mode) {
+      log::warn!("无法设置悬浮窗最小尺寸: {}", e);
+    }
+    if let Err(e) = apply_geometry(&floating_window, &floating_geometry) {
+      log::warn!("无法应用悬浮窗尺寸信息: {}", e);
+    }
+    snap::attach(&floating_window);
+    // 默认隐藏，上次退出时仍显示的话则恢复显示
+    if floating_geometry.visible == Some(true) {
+      floating_window.show()?;
+    } else {
+      floating_window.hide()?;
+    }
+    log::debug!("悬浮窗初始化完成");
+  } else {
+    log::error!("未找到悬浮窗");
+  }
+
+  for window in app.webview_windows().values() {
+    persist::track(window);
+  }
+  if let Err(e) = apply_behavior(app.handle(), &settings) {
+    log::warn!("应用窗口行为设置失败: {}", e);
+  }
+
+  log::info!("窗口初始化完成");
+  Ok(())
+}
+
+/// 显示并聚焦指定窗口，最小化时一并还原。
+pub fn show_window(app: &AppHandle, label: &str) -> tauri::Result<()> {
+  let Some(window) = app.get_webview_window(label) else {
+    return Err(tauri::Error::WindowNotFound);
+  };
+  if window.is_minimized()? {
+    window.unminimize()?;
+  }
+  window.show()?;
+  window.set_focus()?;
+  persist::schedule(&window);
+  tray::refresh(app);
+  Ok(())
+}
+
+pub fn hide_window(app: &AppHandle, label: &str) -> tauri::Result<()> {
+  let Some(window) = app.get_webview_window(label) else {
+    return Err(tauri::Error::WindowNotFound);
+  };
+  window.hide()?;
+  persist::schedule(&window);
+  tray::refresh(app);
+  Ok(())
+}
+
+/// 在显示与隐藏之间切换指定窗口，返回切换后是否可见。
+pub fn toggle_window(app: &AppHandle, label: &str) -> tauri::Result<bool> {
+  let Some(window) = app.get_webview_window(label) else {
+    return Err(tauri::Error::WindowNotFound);
+  };
+  if window.is_visible()? && !window.is_minimized()? {
+    hide_window(app, label)?;
+    Ok(false)
+  } else {
+    show_window(app, label)?;
+    Ok(true)
+  }
+}
+
+/// 把窗口移动到指定的物理像素坐标（与保存的几何信息一致）。
+pub fn set_window_position(app: &AppHandle, label: &str, x: i32, y: i32) -> tauri::Result<()> {
+  let Some(window) = app.get_webview_window(label) else {
+    return Err(tauri::Error::WindowNotFound);
+  };
+  window.set_position(PhysicalPosition::new(x, y))
+}
+
+fn apply_geometry(window: &WebviewWindow, geometry: &WindowGeometry) -> tauri::Result<()> {
+  // 验证窗口大小是否合理（悬浮窗紧凑模式只有一条计时条高）
+  const MIN_SIZE: u32 = 40;
+  const MAX_SIZE: u32 = 10000;
+
+  let size = match (geometry.width, geometry.height) {
+    (Some(width), Some(height))
+      if (MIN_SIZE..=MAX_SIZE).contains(&width) && (MIN_SIZE..=MAX_SIZE).contains(&height) =>
+    {
+      Some((width, height))
+    }
+    (Some(width), Some(height)) => {
+      log::warn!("窗口尺寸异常 ({}x{})，跳过尺寸设置", width, height);
+      None
+    }
+    _ => None,
+  };
+  let current = window.outer_size()?;
+  let (width, height) = size.unwrap_or((current.width, current.height));
+
+  let (Some(x), Some(y)) = (geometry.x, geometry.y) else {
+    if size.is_some() {
+      log::debug!("{} 应用窗口尺寸: {}x{}", window.label(), width, height);
+      window.set_size(PhysicalSize::new(width, height))?;
+    }
+    return Ok(());
+  };
+
+  // 按当前连接的显示器校正位置：Windows 最小化时记录的 (-32000, -32000)、
+  // 拔掉副屏后留下的坐标等都会被移回可见的显示器上。
+  let monitors: Vec<MonitorArea> = window
+    .available_monitors()?
+    .iter()
+    .map(MonitorArea::from_monitor)
+    .collect();
+  let primary = window.primary_monitor()?.and_then(|primary| {
+    monitors
+      .iter()
+      .position(|monitor| monitor.name.as_ref() == primary.name())
+  });
+  let saved = Rect {
+    x,
+    y,
+    width: width as i32,
+    height: height as i32,
+  };
+  let Some(resolved) = geometry::resolve(
+    saved,
+    geometry.monitor.as_deref(),
+    geometry.scale_factor,
+    &monitors,
+    primary,
+  ) else {
+    // 没有可用的显示器信息时，返回错误让调用方居中窗口
+    return Err(tauri::Error::InvalidWindowHandle);
+  };
+  if resolved != saved {
+    log::info!(
+      "窗口位置已按显示器校正: ({}, {}) -> ({}, {})",
+      x, y, resolved.x, resolved.y
+    );
+  }
+
+  log::debug!(
+    "{} 应用窗口几何信息: {}x{} @ ({}, {})",
+    window.label(),
+    resolved.width,
+    resolved.height,
+    resolved.x,
+    resolved.y
+  );
+  window.set_size(PhysicalSize::new(resolved.width as u32, resolved.height as u32))?;
+  window.set_position(PhysicalPosition::new(resolved.x, resolved.y))?;
+  Ok(())
+}
+
@@ -0,0 +1,119 @@
+use serde::Serialize;
+use tauri::{
+  AppHandle, Emitter, LogicalSize, Manager, PhysicalPosition, PhysicalSize, WebviewWindow,
+};
+
+use super::{
+  geometry::{anchor_resize, MonitorArea, Rect},
+  persist,
+};
+use crate::{
+  commands::CommandError,
+  state::AppState,
+  storage::{FloatingMode, UserSettings},
+};
+
+/// 通知悬浮窗前端切换紧凑/展开布局。
+pub const FLOATING_MODE_EVENT: &str = "floating:mode";
+
+const FLOATING_LABEL: &str = "floating";
+/// 紧凑模式的几何信息单独保存，展开模式沿用原来的 `floating`。
+const COMPACT_KEY: &str = "floating-compact";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModePayload {
+  mode: FloatingMode,
+}
+
+/// 各模式的默认尺寸与最小尺寸（逻辑像素）。
+fn mode_sizes(mode: FloatingMode) -> ((f64, f64), (f64, f64)) {
+  match mode {
+    FloatingMode::Compact => ((320.0, 72.0), (240.0, 56.0)),
+    FloatingMode::Expanded => ((360.0, 480.0), (320.0, 360.0)),
+  }
+}
+
+/// 窗口几何信息在 `window_state` 中的键；悬浮窗按当前模式区分。
+pub fn geometry_key(label: &str, settings: &UserSettings) -> String {
+  match (label, settings.floating_mode) {
+    (FLOATING_LABEL, FloatingMode::Compact) => COMPACT_KEY.to_string(),
+    _ => label.to_string(),
+  }
+}
+
+/// 按模式设置悬浮窗的最小尺寸，紧凑模式需要比配置中的下限更矮。
+pub(super) fn apply_constraints(window: &WebviewWindow, mode: FloatingMode) -> tauri::Result<()> {
+  let (_, (min_width, min_height)) = mode_sizes(mode);
+  window.set_min_size(Some(LogicalSize::new(min_width, min_height)))
+}
+
+/// 切换悬浮窗模式：先保存当前模式的几何信息，再换成目标模式记住的尺寸，
+/// 并保持离屏幕角落最近的那个角不动。
+pub fn set_floating_mode(app: &AppHandle, mode: FloatingMode) -> Result<(), CommandError> {
+  let Some(window) = app.get_webview_window(FLOATING_LABEL) else {
+    return Err(tauri::Error::WindowNotFound.into());
+  };
+  let state = app.state::<AppState>();
+  let current_mode = state.store().load_settings()?.floating_mode;
+  if current_mode != mode {
+    resize_for_mode(&window, mode)?;
+    log::info!("悬浮窗切换为 {:?} 模式", mode);
+  }
+  app.emit_to(FLOATING_LABEL, FLOATING_MODE_EVENT, ModePayload { mode })?;
+  Ok(())
+}
+
+fn resize_for_mode(window: &WebviewWindow, mode: FloatingMode) -> Result<(), CommandError> {
+  let app = window.app_handle();
+  let state = app.state::<AppState>();
+  persist::persist(window)?;
+
+  // 自动缩进时窗口在屏幕外，以展开时的位置为准。
+  let current = match state.windows().pinned(FLOATING_LABEL) {
+    Some(rect) => rect,
+    None => {
+      let position = window.outer_position()?;
+      let size = window.outer_size()?;
+      Rect {
+        x: position.x,
+        y: position.y,
+        width: size.width as i32,
+        height: size.height as i32,
+      }
+    }
+  };
+  state.windows().pin(FLOATING_LABEL, None);
+
+  let settings = state.store().update_settings(|settings| {
+    settings.floating_mode = mode;
+    Ok(())
+  })?;
+
+  let scale_factor = window.scale_factor()?;
+  let saved = settings
+    .window_state
+    .get(&geometry_key(FLOATING_LABEL, &settings));
+  let ((default_width, default_height), _) = mode_sizes(mode);
+  let (width, height) = match (saved.width, saved.height) {
+    (Some(width), Some(height)) => (width as i32, height as i32),
+    _ => (
+      (default_width * scale_factor).round() as i32,
+      (default_height * scale_factor).round() as i32,
+    ),
+  };
+
+  let area = window
+    .monitor_from_point(f64::from(current.x), f64::from(current.y))
+    .ok()
+    .flatten()
+    .or_else(|| window.current_monitor().ok().flatten())
+    .map(|monitor| MonitorArea::from_monitor(&monitor).work_area)
+    .unwrap_or(current);
+  let target = anchor_resize(current, area, width, height);
+
+  apply_constraints(window, mode)?;
+  window.set_size(PhysicalSize::new(target.width as u32, target.height as u32))?;
+  window.set_position(PhysicalPosition::new(target.x, target.y))?;
+  Ok(())
+}
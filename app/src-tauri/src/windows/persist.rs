@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use tauri::{Manager, WebviewWindow, WindowEvent};
+
+use super::geometry::Rect;
+use crate::{commands::CommandError, state::AppState, storage::WindowGeometry};
+
+/// 窗口停止移动或缩放后多久写盘。
+const PERSIST_DELAY: Duration = Duration::from_millis(400);
+
+/// 订阅窗口的移动、缩放与缩放比例变化，防抖后保存几何信息。
+pub fn track(window: &WebviewWindow) {
+  let handle = window.clone();
+  window.on_window_event(move |event| {
+    if matches!(
+      event,
+      WindowEvent::Moved(_) | WindowEvent::Resized(_) | WindowEvent::ScaleFactorChanged { .. }
+    ) {
+      schedule(&handle);
+    }
+  });
+}
+
+/// 安排一次防抖保存；显示、隐藏等不会触发窗口事件的变化也通过它记录。
+pub fn schedule(window: &WebviewWindow) {
+  let debouncer = window
+    .app_handle()
+    .state::<AppState>()
+    .windows()
+    .debouncer(window.label());
+  let window = window.clone();
+  debouncer.schedule(PERSIST_DELAY, move || {
+    if let Err(err) = persist(&window) {
+      log::warn!("保存窗口 {} 几何信息失败: {}", window.label(), err);
+    }
+  });
+}
+
+/// 立即保存窗口的几何信息与显示状态。
+pub(super) fn persist(window: &WebviewWindow) -> Result<(), CommandError> {
+  let app = window.app_handle();
+  let state = app.state::<AppState>();
+  let label = window.label();
+
+  // 窗口查询会切到主线程，先在设置锁外取齐，再合并进保存的几何信息。
+  let maximized = window.is_maximized()?;
+  let minimized = window.is_minimized()?;
+  let visible = window.is_visible()?;
+  // 最大化或最小化时保留之前的常规尺寸，还原时才有意义。
+  let normal = if !maximized && !minimized {
+    let rect = match state.windows().pinned(label) {
+      Some(rect) => rect,
+      None => {
+        let position = window.outer_position()?;
+        let size = window.outer_size()?;
+        Rect {
+          x: position.x,
+          y: position.y,
+          width: size.width as i32,
+          height: size.height as i32,
+        }
+      }
+    };
+    let mut geometry = WindowGeometry {
+      x: Some(rect.x),
+      y: Some(rect.y),
+      width: Some(rect.width as u32),
+      height: Some(rect.height as u32),
+      ..WindowGeometry::default()
+    };
+    super::annotate_geometry(window, &mut geometry);
+    Some(geometry)
+  } else {
+    None
+  };
+
+  state.store().update_settings(|settings| {
+    let key = super::geometry_key(label, settings);
+    let mut geometry = normal.unwrap_or_else(|| settings.window_state.get(&key));
+    geometry.maximized = maximized;
+    geometry.visible = Some(visible);
+    settings.window_state.set(&key, geometry);
+    Ok(())
+  })?;
+  Ok(())
+}
@@ -0,0 +1,319 @@
+use std::{collections::HashMap, sync::Arc};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use super::{debounce::Debouncer, geometry::Rect, persist};
+use crate::{
+  i18n,
+  state::AppState,
+  storage::{StorageError, UserSettings},
+};
+
+/// 可按类型打开的窗口；主窗口与悬浮窗在配置中声明，其余按需创建。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowKind {
+  Main,
+  Floating,
+  QuickAdd,
+  Statistics,
+  ProjectFloating,
+}
+
+/// 按需创建窗口时的默认外观。
+struct WindowSpec {
+  title: String,
+  width: f64,
+  height: f64,
+  min_size: Option<(f64, f64)>,
+  decorations: bool,
+  resizable: bool,
+  skip_taskbar: bool,
+  transparent: bool,
+  always_on_top: bool,
+}
+
+impl WindowKind {
+  /// 窗口标签；项目悬浮窗用项目名的十六进制编码区分，保证标签只含合法字符。
+  pub fn label(self, project: Option<&str>) -> Result<String, StorageError> {
+    let label = match self {
+      WindowKind::Main => "main".to_string(),
+      WindowKind::Floating => "floating".to_string(),
+      WindowKind::QuickAdd => "quick-add".to_string(),
+      WindowKind::Statistics => "statistics".to_string(),
+      WindowKind::ProjectFloating => {
+        let project = project_name(project)
+          .ok_or_else(|| StorageError::validation(i18n::t!("window.projectRequired")))?;
+        let encoded: String = project.bytes().map(|byte| format!("{byte:02x}")).collect();
+        format!("project-{encoded}")
+      }
+    };
+    Ok(label)
+  }
+
+  /// 悬浮窗类窗口：跟随悬浮窗的置顶、透明度等行为设置。
+  pub fn is_floating(self) -> bool {
+    matches!(self, WindowKind::Floating | WindowKind::ProjectFloating)
+  }
+
+  fn route(self) -> &'static str {
+    match self {
+      WindowKind::Main => "main",
+      WindowKind::Floating => "floating",
+      WindowKind::QuickAdd => "quick-add",
+      WindowKind::Statistics => "statistics",
+      WindowKind::ProjectFloating => "project-floating",
+    }
+  }
+
+  fn spec(self, settings: &UserSettings) -> Option<WindowSpec> {
+    match self {
+      WindowKind::Main | WindowKind::Floating => None,
+      WindowKind::QuickAdd => Some(WindowSpec {
+        title: i18n::t!("window.quickAdd"),
+        width: 480.0,
+        height: 160.0,
+        min_size: None,
+        decorations: false,
+        resizable: false,
+        skip_taskbar: true,
+        transparent: true,
+        always_on_top: true,
+      }),
+      WindowKind::Statistics => Some(WindowSpec {
+        title: i18n::t!("window.statistics"),
+        width: 760.0,
+        height: 540.0,
+        min_size: Some((480.0, 360.0)),
+        decorations: true,
+        resizable: true,
+        skip_taskbar: false,
+        transparent: false,
+        always_on_top: false,
+      }),
+      WindowKind::ProjectFloating => Some(WindowSpec {
+        title: "FocusTodo Mini".to_string(),
+        width: 360.0,
+        height: 480.0,
+        min_size: Some((320.0, 360.0)),
+        decorations: false,
+        resizable: true,
+        skip_taskbar: true,
+        transparent: true,
+        always_on_top: settings.always_on_top,
+      }),
+    }
+  }
+}
+
+/// 窗口注册表：按需创建的窗口类型，以及各窗口几何信息持久化所需的状态。
+#[derive(Default)]
+pub struct WindowRegistry {
+  kinds: Mutex<HashMap<String, WindowKind>>,
+  debouncers: Mutex<HashMap<String, Arc<Debouncer>>>,
+  pinned: Mutex<HashMap<String, Rect>>,
+}
+
+impl WindowRegistry {
+  /// 窗口被程序临时移开（如悬浮窗自动缩进）时，保存固定的矩形而不是当前位置。
+  pub fn pin(&self, label: &str, rect: Option<Rect>) {
+    let mut pinned = self.pinned.lock();
+    match rect {
+      Some(rect) => pinned.insert(label.to_string(), rect),
+      None => pinned.remove(label),
+    };
+  }
+
+  /// 当前打开的窗口标签；`main` 与 `floating` 在配置中声明，始终计入。
+  pub(super) fn labels(&self, filter: impl Fn(WindowKind) -> bool) -> Vec<String> {
+    let declared = [
+      ("main", WindowKind::Main),
+      ("floating", WindowKind::Floating),
+    ];
+    let mut labels: Vec<String> = declared
+      .into_iter()
+      .filter(|(_, kind)| filter(*kind))
+      .map(|(label, _)| label.to_string())
+      .collect();
+    labels.extend(
+      self
+        .kinds
+        .lock()
+        .iter()
+        .filter(|(_, kind)| filter(**kind))
+        .map(|(label, _)| label.clone()),
+    );
+    labels
+  }
+
+  pub(super) fn pinned(&self, label: &str) -> Option<Rect> {
+    self.pinned.lock().get(label).copied()
+  }
+
+  pub(super) fn debouncer(&self, label: &str) -> Arc<Debouncer> {
+    self
+      .debouncers
+      .lock()
+      .entry(label.to_string())
+      .or_default()
+      .clone()
+  }
+
+  fn forget(&self, label: &str) {
+    self.kinds.lock().remove(label);
+    self.debouncers.lock().remove(label);
+    self.pinned.lock().remove(label);
+  }
+}
+
+/// 打开指定类型的窗口，已存在时只显示并聚焦。
+///
+/// 在 Windows 上从主线程同步创建窗口会死锁，只能在异步命令或后台线程中调用。
+pub fn open_window(
+  app: &AppHandle,
+  kind: WindowKind,
+  label: &str,
+  project: Option<&str>,
+) -> tauri::Result<()> {
+  if app.get_webview_window(label).is_some() {
+    return super::show_window(app, label);
+  }
+  let state = app.state::<AppState>();
+  let settings = state.store().load_settings().unwrap_or_default();
+  let Some(spec) = kind.spec(&settings) else {
+    return Err(tauri::Error::WindowNotFound);
+  };
+
+  let project = project_name(project);
+  let mut url = format!("index.html?window={}", kind.route());
+  if let Some(project) = project {
+    url.push_str("&project=");
+    url.push_str(&encode_query(project));
+  }
+  let title = match project {
+    Some(project) => format!("{} · {}", spec.title, project),
+    None => spec.title.clone(),
+  };
+  let preference = settings
+    .window_preferences
+    .get(label)
+    .cloned()
+    .unwrap_or_default();
+
+  let mut builder = WebviewWindowBuilder::new(app, label, WebviewUrl::App(url.into()))
+    .title(title)
+    .inner_size(spec.width, spec.height)
+    .decorations(spec.decorations)
+    .resizable(spec.resizable)
+    .skip_taskbar(spec.skip_taskbar)
+    .always_on_top(preference.always_on_top.unwrap_or(spec.always_on_top))
+    .visible_on_all_workspaces(
+      preference
+        .visible_on_all_workspaces
+        .unwrap_or(settings.visible_on_all_workspaces),
+    )
+    .visible(false)
+    .center();
+  if let Some((width, height)) = spec.min_size {
+    builder = builder.min_inner_size(width, height);
+  }
+  // macOS 上的透明窗口需要私有 API，那里保持不透明。
+  #[cfg(not(target_os = "macos"))]
+  {
+    builder = builder.transparent(spec.transparent);
+  }
+  #[cfg(target_os = "macos")]
+  let _ = spec.transparent;
+
+  let window = builder.build()?;
+  attach(&window, kind);
+
+  let geometry = settings.window_state.get(label);
+  if let Err(err) = super::apply_geometry(&window, &geometry) {
+    log::warn!("无法应用窗口 {} 的几何信息: {}", label, err);
+  }
+  log::info!("已创建窗口 {}", label);
+  super::show_window(app, label)
+}
+
+/// 聚焦已打开的窗口；窗口不存在时返回 `WindowNotFound`。
+pub fn focus_window(app: &AppHandle, label: &str) -> tauri::Result<()> {
+  super::show_window(app, label)
+}
+
+/// 关闭窗口：按需创建的窗口立即保存几何信息后销毁，主窗口与悬浮窗只隐藏。
+/// 直接销毁而不走 `close`，前端拦截关闭请求后调用这里时不会再次触发关闭请求。
+pub fn close_window(app: &AppHandle, kind: WindowKind, label: &str) -> tauri::Result<()> {
+  let Some(window) = app.get_webview_window(label) else {
+    return Ok(());
+  };
+  match kind {
+    WindowKind::Main | WindowKind::Floating => super::hide_window(app, label),
+    _ => {
+      if let Err(err) = persist::persist(&window) {
+        log::warn!("保存窗口 {} 几何信息失败: {}", label, err);
+      }
+      window.destroy()
+    }
+  }
+}
+
+/// 把保存的窗口偏好应用到已打开的窗口上。
+pub fn apply_preference(
+  app: &AppHandle,
+  label: &str,
+  settings: &UserSettings,
+) -> tauri::Result<()> {
+  let (Some(window), Some(preference)) = (
+    app.get_webview_window(label),
+    settings.window_preferences.get(label),
+  ) else {
+    return Ok(());
+  };
+  if let Some(always_on_top) = preference.always_on_top {
+    window.set_always_on_top(always_on_top)?;
+  }
+  if let Some(visible) = preference.visible_on_all_workspaces {
+    window.set_visible_on_all_workspaces(visible)?;
+  }
+  Ok(())
+}
+
+fn attach(window: &WebviewWindow, kind: WindowKind) {
+  window
+    .state::<AppState>()
+    .windows()
+    .kinds
+    .lock()
+    .insert(window.label().to_string(), kind);
+  persist::track(window);
+  let handle = window.clone();
+  window.on_window_event(move |event| match event {
+    // 快速新建弹窗失去焦点即关闭，与系统的快速输入框行为一致。
+    WindowEvent::Focused(false) if kind == WindowKind::QuickAdd => {
+      let _ = handle.close();
+    }
+    WindowEvent::Destroyed => {
+      handle.state::<AppState>().windows().forget(handle.label());
+    }
+    _ => {}
+  });
+}
+
+fn project_name(project: Option<&str>) -> Option<&str> {
+  project.map(str::trim).filter(|project| !project.is_empty())
+}
+
+fn encode_query(value: &str) -> String {
+  value
+    .bytes()
+    .map(|byte| match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+        (byte as char).to_string()
+      }
+      other => format!("%{other:02X}"),
+    })
+    .collect()
+}
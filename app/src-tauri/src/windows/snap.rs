@@ -0,0 +1,495 @@
+use std::{
+  sync::Arc,
+  thread,
+  time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use tauri::{Manager, PhysicalPosition, WebviewWindow, WindowEvent};
+
+use super::{
+  debounce::Debouncer,
+  geometry::{MonitorArea, Rect},
+};
+use crate::state::AppState;
+
+/// 拖动停止后等待的时间，避免在拖动过程中与系统争抢窗口位置。
+const SETTLE_DELAY: Duration = Duration::from_millis(250);
+/// 程序主动移动窗口后忽略随之而来的 Moved 事件的时长。
+const PROGRAMMATIC_MOVE_GRACE: Duration = Duration::from_millis(400);
+const HOVER_POLL_INTERVAL: Duration = Duration::from_millis(150);
+/// 鼠标离开窗口多久后缩进屏幕边缘。
+const HIDE_DELAY: Duration = Duration::from_millis(600);
+/// 自动隐藏时留在屏幕内的宽度（逻辑像素）。
+const PEEK_SIZE: f64 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+  Left,
+  Right,
+  Top,
+  Bottom,
+}
+
+/// 吸附结果：水平与垂直方向各自最多贴住一条边，两者都有即为贴角。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dock {
+  pub horizontal: Option<Edge>,
+  pub vertical: Option<Edge>,
+}
+
+impl Dock {
+  fn is_docked(&self) -> bool {
+    self.horizontal.is_some() || self.vertical.is_some()
+  }
+}
+
+/// 计算窗口在工作区内吸附后的位置；距离边缘超过阈值的方向保持不变。
+pub fn snap(window: Rect, area: Rect, threshold: i32) -> (i32, i32, Dock) {
+  let mut dock = Dock::default();
+  let left = (window.x - area.x).abs();
+  let right = (area.right() - window.right()).abs();
+  let x = if left <= threshold && left <= right {
+    dock.horizontal = Some(Edge::Left);
+    area.x
+  } else if right <= threshold {
+    dock.horizontal = Some(Edge::Right);
+    area.right() - window.width
+  } else {
+    window.x
+  };
+
+  let top = (window.y - area.y).abs();
+  let bottom = (area.bottom() - window.bottom()).abs();
+  let y = if top <= threshold && top <= bottom {
+    dock.vertical = Some(Edge::Top);
+    area.y
+  } else if bottom <= threshold {
+    dock.vertical = Some(Edge::Bottom);
+    area.bottom() - window.height
+  } else {
+    window.y
+  };
+  (x, y, dock)
+}
+
+/// 自动隐藏时窗口的位置：优先沿左右边缘缩进，只露出 `peek` 宽的一条。
+pub fn hidden_position(window: Rect, area: Rect, dock: Dock, peek: i32) -> Option<(i32, i32)> {
+  match (dock.horizontal, dock.vertical) {
+    (Some(Edge::Left), _) => Some((area.x - window.width + peek, window.y)),
+    (Some(Edge::Right), _) => Some((area.right() - peek, window.y)),
+    (_, Some(Edge::Top)) => Some((window.x, area.y - window.height + peek)),
+    (_, Some(Edge::Bottom)) => Some((window.x, area.bottom() - peek)),
+    _ => None,
+  }
+}
+
+#[derive(Default)]
+struct SnapState {
+  ignore_until: Option<Instant>,
+  /// 用户每次拖动都会递增，旧的悬停监视线程据此退出。
+  generation: u64,
+  dock: Dock,
+  revealed: Option<Rect>,
+  area: Option<Rect>,
+  hidden: bool,
+}
+
+struct FloatingSnap {
+  window: WebviewWindow,
+  state: Mutex<SnapState>,
+  debouncer: Arc<Debouncer>,
+}
+
+/// 监听悬浮窗移动：停下后按设置吸附到当前显示器最近的边缘或角落。
+pub fn attach(window: &WebviewWindow) {
+  let snap = Arc::new(FloatingSnap {
+    window: window.clone(),
+    state: Mutex::new(SnapState::default()),
+    debouncer: Arc::new(Debouncer::default()),
+  });
+  window.on_window_event(move |event| {
+    if let WindowEvent::Moved(_) = event {
+      snap.on_moved();
+    }
+  });
+}
+
+impl FloatingSnap {
+  fn on_moved(self: &Arc<Self>) {
+    {
+      let mut state = self.state.lock();
+      if state
+        .ignore_until
+        .is_some_and(|until| Instant::now() < until)
+      {
+        return;
+      }
+      state.generation += 1;
+      state.dock = Dock::default();
+      state.hidden = false;
+    }
+    self
+      .window
+      .app_handle()
+      .state::<AppState>()
+      .windows()
+      .pin(self.window.label(), None);
+    let snap = Arc::clone(self);
+    self.debouncer.schedule(SETTLE_DELAY, move || {
+      if let Err(err) = snap.settle() {
+        log::warn!("悬浮窗吸附失败: {}", err);
+      }
+    });
+  }
+
+  fn settle(self: &Arc<Self>) -> tauri::Result<()> {
+    let app = self.window.app_handle();
+    let settings = app
+      .state::<AppState>()
+      .store()
+      .load_settings()
+      .unwrap_or_default();
+    if !settings.snap_edge {
+      return Ok(());
+    }
+    let Some(monitor) = self.window.current_monitor()? else {
+      return Ok(());
+    };
+    let position = self.window.outer_position()?;
+    let size = self.window.outer_size()?;
+    let window = Rect {
+      x: position.x,
+      y: position.y,
+      width: size.width as i32,
+      height: size.height as i32,
+    };
+    let area = MonitorArea::from_monitor(&monitor).work_area;
+    let threshold = (f64::from(settings.snap_threshold) * monitor.scale_factor()).round() as i32;
+    let (x, y, dock) = snap(window, area, threshold);
+    let revealed = Rect { x, y, ..window };
+
+    let generation = {
+      let mut state = self.state.lock();
+      state.dock = dock;
+      state.revealed = Some(revealed);
+      state.area = Some(area);
+      state.generation
+    };
+    if (x, y) != (window.x, window.y) {
+      self.move_to(x, y)?;
+    }
+
+    if settings.floating_auto_hide && dock.is_docked() {
+      self.watch_hover(generation, monitor.scale_factor());
+    }
+    Ok(())
+  }
+
+  fn move_to(&self, x: i32, y: i32) -> tauri::Result<()> {
+    self.state.lock().ignore_until = Some(Instant::now() + PROGRAMMATIC_MOVE_GRACE);
+    self.window.set_position(PhysicalPosition::new(x, y))
+  }
+
+  /// 轮询鼠标位置：离开窗口一段时间后缩进边缘，移到露出的边缘时展开。
+  fn watch_hover(self: &Arc<Self>, generation: u64, scale_factor: f64) {
+    let snap = Arc::clone(self);
+    let peek = (PEEK_SIZE * scale_factor).round().max(1.0) as i32;
+    thread::spawn(move || {
+      let mut outside_since: Option<Instant> = None;
+      loop {
+        thread::sleep(HOVER_POLL_INTERVAL);
+        let (dock, revealed, area, hidden) = {
+          let state = snap.state.lock();
+          if state.generation != generation {
+            return;
+          }
+          let (Some(revealed), Some(area)) = (state.revealed, state.area) else {
+            return;
+          };
+          (state.dock, revealed, area, state.hidden)
+        };
+        let Some((hidden_x, hidden_y)) = hidden_position(revealed, area, dock, peek) else {
+          return;
+        };
+        if !snap.window.is_visible().unwrap_or(false) {
+          continue;
+        }
+        let Ok(cursor) = snap.window.app_handle().cursor_position() else {
+          continue;
+        };
+
+        let result = if hidden {
+          // 缩进后仍露在屏幕内的那一条。
+          let strip = Rect {
+            x: hidden_x,
+            y: hidden_y,
+            ..revealed
+          }
+          .intersection(area);
+          if strip.contains(cursor.x, cursor.y) {
+            outside_since = None;
+            snap.set_hidden(false, revealed.x, revealed.y)
+          } else {
+            Ok(())
+          }
+        } else if revealed.contains(cursor.x, cursor.y) {
+          outside_since = None;
+          Ok(())
+        } else {
+          let since = *outside_since.get_or_insert_with(Instant::now);
+          if since.elapsed() >= HIDE_DELAY {
+            outside_since = None;
+            snap.set_hidden(true, hidden_x, hidden_y)
+          } else {
+            Ok(())
+          }
+        };
+        if let Err(err) = result {
+          log::warn!("悬浮窗自动隐藏失败: {}", err);
+          return;
+        }
+      }
+    });
+  }
+
+  fn set_hidden(&self, hidden: bool, x: i32, y: i32) -> tauri::Result<()> {
+    let revealed = {
+      let mut state = self.state.lock();
+      state.hidden = hidden;
+      state.revealed
+    };
+    // 缩进期间保存展开时的位置，避免下次启动时窗口停在屏幕外。
+    self
+      .window
+      .app_handle()
+      .state::<AppState>()
+      .windows()
+      .pin(self.window.label(), revealed.filter(|_| hidden));
+    self.move_to(x, y)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const AREA: Rect = Rect {
+    x: 0,
+    y: 0,
+    width: 1920,
+    height: 1040,
+  };
+  const THRESHOLD: i32 = 16;
+
+  fn window(x: i32, y: i32) -> Rect {
+    Rect {
+      x,
+      y,
+      width: 300,
+      height: 200,
+    }
+  }
+
+  fn dock(horizontal: Option<Edge>, vertical: Option<Edge>) -> Dock {
+    Dock {
+      horizontal,
+      vertical,
+    }
+  }
+
+  #[test]
+  fn snaps_to_each_edge_and_corner() {
+    let cases = [
+      (
+        "左",
+        window(10, 400),
+        (0, 400),
+        dock(Some(Edge::Left), None),
+      ),
+      (
+        "右",
+        window(1615, 400),
+        (1620, 400),
+        dock(Some(Edge::Right), None),
+      ),
+      ("上", window(800, 12), (800, 0), dock(None, Some(Edge::Top))),
+      (
+        "下",
+        window(800, 837),
+        (800, 840),
+        dock(None, Some(Edge::Bottom)),
+      ),
+      (
+        "左上",
+        window(5, 5),
+        (0, 0),
+        dock(Some(Edge::Left), Some(Edge::Top)),
+      ),
+      (
+        "右下",
+        window(1610, 830),
+        (1620, 840),
+        dock(Some(Edge::Right), Some(Edge::Bottom)),
+      ),
+      (
+        "超出左边",
+        window(-10, 400),
+        (0, 400),
+        dock(Some(Edge::Left), None),
+      ),
+      ("中间", window(800, 400), (800, 400), Dock::default()),
+    ];
+    for (name, rect, position, expected) in cases {
+      assert_eq!(
+        snap(rect, AREA, THRESHOLD),
+        (position.0, position.1, expected),
+        "{name}"
+      );
+    }
+  }
+
+  #[test]
+  fn threshold_is_inclusive() {
+    let cases = [
+      (window(THRESHOLD, 400), 0, Some(Edge::Left)),
+      (window(THRESHOLD + 1, 400), THRESHOLD + 1, None),
+      (window(1620 - THRESHOLD, 400), 1620, Some(Edge::Right)),
+      (
+        window(1620 - THRESHOLD - 1, 400),
+        1620 - THRESHOLD - 1,
+        None,
+      ),
+    ];
+    for (rect, x, horizontal) in cases {
+      let (snapped_x, _, dock) = snap(rect, AREA, THRESHOLD);
+      assert_eq!((snapped_x, dock.horizontal), (x, horizontal), "{rect:?}");
+    }
+  }
+
+  #[test]
+  fn equal_distance_prefers_left_and_top() {
+    let rect = Rect {
+      x: 5,
+      y: 5,
+      width: AREA.width - 10,
+      height: AREA.height - 10,
+    };
+    assert_eq!(
+      snap(rect, AREA, THRESHOLD),
+      (0, 0, dock(Some(Edge::Left), Some(Edge::Top)))
+    );
+  }
+
+  #[test]
+  fn snaps_within_secondary_monitors() {
+    let right_monitor = Rect {
+      x: 1920,
+      y: 0,
+      width: 2560,
+      height: 1400,
+    };
+    let left_monitor = Rect {
+      x: -1280,
+      y: 200,
+      width: 1280,
+      height: 984,
+    };
+    let cases = [
+      (
+        window(1930, 600),
+        right_monitor,
+        (1920, 600),
+        dock(Some(Edge::Left), None),
+      ),
+      (
+        window(4170, 1195),
+        right_monitor,
+        (4180, 1200),
+        dock(Some(Edge::Right), Some(Edge::Bottom)),
+      ),
+      (
+        window(-1275, 210),
+        left_monitor,
+        (-1280, 200),
+        dock(Some(Edge::Left), Some(Edge::Top)),
+      ),
+      (
+        window(-310, 500),
+        left_monitor,
+        (-300, 500),
+        dock(Some(Edge::Right), None),
+      ),
+    ];
+    for (rect, area, position, expected) in cases {
+      assert_eq!(
+        snap(rect, area, THRESHOLD),
+        (position.0, position.1, expected),
+        "{rect:?} in {area:?}"
+      );
+    }
+  }
+
+  #[test]
+  fn hidden_position_leaves_peek_on_screen() {
+    let peek = 6;
+    let cases = [
+      (
+        window(0, 400),
+        dock(Some(Edge::Left), None),
+        Some((-294, 400)),
+      ),
+      (
+        window(1620, 400),
+        dock(Some(Edge::Right), None),
+        Some((1914, 400)),
+      ),
+      (
+        window(800, 0),
+        dock(None, Some(Edge::Top)),
+        Some((800, -194)),
+      ),
+      (
+        window(800, 840),
+        dock(None, Some(Edge::Bottom)),
+        Some((800, 1034)),
+      ),
+      // 贴角时优先沿左右边缘缩进。
+      (
+        window(0, 840),
+        dock(Some(Edge::Left), Some(Edge::Bottom)),
+        Some((-294, 840)),
+      ),
+      (window(800, 400), Dock::default(), None),
+    ];
+    for (rect, dock, expected) in cases {
+      assert_eq!(
+        hidden_position(rect, AREA, dock, peek),
+        expected,
+        "{dock:?}"
+      );
+      if let Some((x, y)) = expected {
+        let strip = Rect { x, y, ..rect }.intersection(AREA);
+        assert_eq!(strip.width.min(strip.height), peek, "{dock:?} 露出的宽度");
+      }
+    }
+  }
+
+  #[test]
+  fn hidden_position_on_secondary_monitor() {
+    let area = Rect {
+      x: 1920,
+      y: 0,
+      width: 2560,
+      height: 1400,
+    };
+    assert_eq!(
+      hidden_position(window(4180, 300), area, dock(Some(Edge::Right), None), 6),
+      Some((4474, 300))
+    );
+    assert_eq!(
+      hidden_position(window(1920, 300), area, dock(Some(Edge::Left), None), 6),
+      Some((1626, 300))
+    );
+  }
+}
@@ -0,0 +1,390 @@
+use std::collections::HashSet;
+
+use crate::{
+  i18n,
+  storage::{StatusChange, StorageError, TodoItem, WorkflowSetting, WorkflowStatus},
+};
+
+/// 校验工作流配置：状态 id 唯一，初始状态存在且未完成，至少有一个完成状态，切换目标都存在。
+pub fn validate(workflow: &WorkflowSetting) -> Result<(), StorageError> {
+  if workflow.statuses.is_empty() {
+    return Err(StorageError::invalid_field(
+      "workflow.statuses",
+      i18n::t!("workflow.empty"),
+    ));
+  }
+  let mut ids = HashSet::new();
+  for status in &workflow.statuses {
+    if status.id.trim().is_empty() || !ids.insert(status.id.as_str()) {
+      return Err(StorageError::invalid_field(
+        "workflow.statuses",
+        i18n::t!("workflow.duplicateStatus", id = status.id),
+      ));
+    }
+  }
+  for target in workflow
+    .statuses
+    .iter()
+    .flat_map(|status| &status.transitions)
+  {
+    if !ids.contains(target.as_str()) {
+      return Err(StorageError::invalid_field(
+        "workflow.statuses",
+        i18n::t!("workflow.unknownStatus", id = target),
+      ));
+    }
+  }
+  match find(workflow, &workflow.initial) {
+    None => Err(StorageError::invalid_field(
+      "workflow.initial",
+      i18n::t!("workflow.unknownStatus", id = workflow.initial),
+    )),
+    Some(initial) if initial.done => Err(StorageError::invalid_field(
+      "workflow.initial",
+      i18n::t!("workflow.initialDone"),
+    )),
+    Some(_) if !workflow.statuses.iter().any(|status| status.done) => Err(
+      StorageError::invalid_field("workflow.statuses", i18n::t!("workflow.noDoneStatus")),
+    ),
+    Some(_) => Ok(()),
+  }
+}
+
+/// 待办当前所处的状态；旧数据或已从配置中删除的状态按 `completed` 推断。
+pub fn current<'a>(workflow: &'a WorkflowSetting, todo: &TodoItem) -> Option<&'a WorkflowStatus> {
+  todo
+    .status
+    .as_deref()
+    .and_then(|id| find(workflow, id))
+    .or_else(|| {
+      if todo.completed {
+        done_status(workflow)
+      } else {
+        find(workflow, &workflow.initial)
+      }
+    })
+}
+
+/// 从当前状态允许切换到的状态。
+pub fn allowed_transitions(workflow: &WorkflowSetting, todo: &TodoItem) -> Vec<WorkflowStatus> {
+  let Some(current) = current(workflow, todo) else {
+    return Vec::new();
+  };
+  current
+    .transitions
+    .iter()
+    .filter_map(|id| find(workflow, id))
+    .map(|status| WorkflowStatus {
+      name: status_name(status),
+      ..status.clone()
+    })
+    .collect()
+}
+
+/// 状态的显示名称：未自定义名称的内置状态按 id 取当前语言的文案，其他状态退回 id。
+pub fn status_name(status: &WorkflowStatus) -> String {
+  if !status.name.trim().is_empty() {
+    return status.name.clone();
+  }
+  match status.id.as_str() {
+    "backlog" => i18n::t!("workflow.status.backlog"),
+    "todo" => i18n::t!("workflow.status.todo"),
+    "inProgress" => i18n::t!("workflow.status.inProgress"),
+    "blocked" => i18n::t!("workflow.status.blocked"),
+    "done" => i18n::t!("workflow.status.done"),
+    _ => status.id.clone(),
+  }
+}
+
+/// 按配置校验后切换状态，并记录历史。
+pub fn transition(
+  workflow: &WorkflowSetting,
+  todo: &mut TodoItem,
+  to: &str,
+  now: &str,
+) -> Result<(), StorageError> {
+  let target = find(workflow, to).ok_or_else(|| {
+    StorageError::invalid_field("status", i18n::t!("workflow.unknownStatus", id = to))
+  })?;
+  if let Some(current) = current(workflow, todo) {
+    if current.id == target.id {
+      return Err(StorageError::invalid_field(
+        "status",
+        i18n::t!("workflow.sameStatus", status = status_name(current)),
+      ));
+    }
+    if !current.transitions.contains(&target.id) {
+      return Err(StorageError::invalid_field(
+        "status",
+        i18n::t!(
+          "workflow.transitionNotAllowed",
+          from = status_name(current),
+          to = status_name(target)
+        ),
+      ));
+    }
+  }
+  apply(workflow, todo, target, now);
+  Ok(())
+}
+
+/// 兼容只修改完成标记的旧接口：完成时进入当前状态允许的第一个完成状态，取消完成时回到初始状态。
+///
+/// 与 [`transition`] 一样遵守配置中的切换规则，不允许的切换返回校验错误。
+pub fn set_completed(
+  workflow: &WorkflowSetting,
+  todo: &mut TodoItem,
+  completed: bool,
+  now: &str,
+) -> Result<(), StorageError> {
+  let current = match current(workflow, todo) {
+    Some(current) if current.done != completed => current,
+    _ => {
+      if todo.completed != completed {
+        todo.completed = completed;
+        todo.completed_at = completed.then(|| now.to_string());
+        todo.updated_at = now.to_string();
+      }
+      return Ok(());
+    }
+  };
+  let target = if completed {
+    current
+      .transitions
+      .iter()
+      .filter_map(|id| find(workflow, id))
+      .find(|status| status.done)
+      .or_else(|| done_status(workflow))
+  } else {
+    find(workflow, &workflow.initial)
+  };
+  let Some(target) = target else {
+    return Err(StorageError::invalid_field(
+      "status",
+      i18n::t!("workflow.noDoneStatus"),
+    ));
+  };
+  if !current.transitions.contains(&target.id) {
+    return Err(StorageError::invalid_field(
+      "status",
+      i18n::t!(
+        "workflow.transitionNotAllowed",
+        from = status_name(current),
+        to = status_name(target)
+      ),
+    ));
+  }
+  apply(workflow, todo, target, now);
+  Ok(())
+}
+
+/// 写入新状态与历史，并保持 `completed`/`completed_at` 与状态一致。
+fn apply(workflow: &WorkflowSetting, todo: &mut TodoItem, target: &WorkflowStatus, now: &str) {
+  let from = current(workflow, todo).map(|status| status.id.clone());
+  todo.status_history.push(StatusChange {
+    from,
+    to: target.id.clone(),
+    at: now.to_string(),
+  });
+  todo.status = Some(target.id.clone());
+  if target.done != todo.completed {
+    todo.completed = target.done;
+    todo.completed_at = target.done.then(|| now.to_string());
+  }
+  todo.updated_at = now.to_string();
+}
+
+fn find<'a>(workflow: &'a WorkflowSetting, id: &str) -> Option<&'a WorkflowStatus> {
+  workflow.statuses.iter().find(|status| status.id == id)
+}
+
+fn done_status(workflow: &WorkflowSetting) -> Option<&WorkflowStatus> {
+  workflow.statuses.iter().find(|status| status.done)
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  const NOW: &str = "2026-03-02T09:00:00Z";
+
+  fn todo(status: Option<&str>, completed: bool) -> TodoItem {
+    serde_json::from_value(json!({
+      "id": "a",
+      "title": "写周报",
+      "status": status,
+      "completed": completed,
+      "completedAt": completed.then_some("2026-03-01T08:00:00Z"),
+      "createdAt": "2026-03-01T00:00:00Z",
+      "updatedAt": "2026-03-01T00:00:00Z",
+    }))
+    .unwrap()
+  }
+
+  /// 把默认工作流改坏的一种方式。
+  type Breakage = fn(&mut WorkflowSetting);
+
+  fn invalid_field(result: Result<(), StorageError>) -> Option<String> {
+    match result {
+      Err(StorageError::Validation { field, .. }) => field,
+      other => panic!("应返回校验错误: {other:?}"),
+    }
+  }
+
+  #[test]
+  fn default_workflow_is_valid() {
+    assert!(validate(&WorkflowSetting::default()).is_ok());
+  }
+
+  #[test]
+  fn validate_rejects_broken_workflows() {
+    let cases: [(&str, Breakage, &str); 6] = [
+      ("空", |w| w.statuses.clear(), "workflow.statuses"),
+      (
+        "重复 id",
+        |w| w.statuses[1].id = "backlog".to_string(),
+        "workflow.statuses",
+      ),
+      (
+        "未知切换目标",
+        |w| w.statuses[0].transitions.push("archived".to_string()),
+        "workflow.statuses",
+      ),
+      (
+        "未知初始状态",
+        |w| w.initial = "archived".to_string(),
+        "workflow.initial",
+      ),
+      (
+        "初始状态已完成",
+        |w| w.initial = "done".to_string(),
+        "workflow.initial",
+      ),
+      (
+        "没有完成状态",
+        |w| w.statuses[4].done = false,
+        "workflow.statuses",
+      ),
+    ];
+    for (name, break_workflow, field) in cases {
+      let mut workflow = WorkflowSetting::default();
+      break_workflow(&mut workflow);
+      assert_eq!(
+        invalid_field(validate(&workflow)).as_deref(),
+        Some(field),
+        "{name}"
+      );
+    }
+  }
+
+  #[test]
+  fn transition_records_history_and_completion() {
+    let workflow = WorkflowSetting::default();
+    let mut item = todo(Some("todo"), false);
+    transition(&workflow, &mut item, "inProgress", NOW).unwrap();
+    transition(&workflow, &mut item, "done", NOW).unwrap();
+
+    assert_eq!(item.status.as_deref(), Some("done"));
+    assert!(item.completed);
+    assert_eq!(item.completed_at.as_deref(), Some(NOW));
+    assert_eq!(item.updated_at, NOW);
+    let history: Vec<_> = item
+      .status_history
+      .iter()
+      .map(|change| (change.from.as_deref(), change.to.as_str()))
+      .collect();
+    assert_eq!(
+      history,
+      [(Some("todo"), "inProgress"), (Some("inProgress"), "done")]
+    );
+  }
+
+  #[test]
+  fn transition_rejects_forbidden_targets() {
+    let workflow = WorkflowSetting::default();
+    for (from, to) in [
+      ("blocked", "done"),
+      ("done", "inProgress"),
+      ("todo", "todo"),
+      ("todo", "archived"),
+    ] {
+      let mut item = todo(Some(from), from == "done");
+      let before = item.clone();
+      assert_eq!(
+        invalid_field(transition(&workflow, &mut item, to, NOW)).as_deref(),
+        Some("status"),
+        "{from} -> {to}"
+      );
+      assert_eq!(item.status, before.status, "{from} -> {to}");
+      assert_eq!(item.completed_at, before.completed_at, "{from} -> {to}");
+      assert!(item.status_history.is_empty(), "{from} -> {to}");
+    }
+  }
+
+  #[test]
+  fn legacy_todo_without_status_follows_completed_flag() {
+    let workflow = WorkflowSetting::default();
+    assert_eq!(current(&workflow, &todo(None, false)).unwrap().id, "todo");
+    assert_eq!(current(&workflow, &todo(None, true)).unwrap().id, "done");
+    // 已从配置中删除的状态同样按完成标记推断。
+    assert_eq!(
+      current(&workflow, &todo(Some("archived"), false))
+        .unwrap()
+        .id,
+      "todo"
+    );
+  }
+
+  #[test]
+  fn set_completed_round_trip() {
+    let workflow = WorkflowSetting::default();
+    let mut item = todo(Some("inProgress"), false);
+
+    set_completed(&workflow, &mut item, true, NOW).unwrap();
+    assert_eq!(item.status.as_deref(), Some("done"));
+    assert!(item.completed);
+    assert_eq!(item.completed_at.as_deref(), Some(NOW));
+
+    let later = "2026-03-02T10:00:00Z";
+    set_completed(&workflow, &mut item, false, later).unwrap();
+    assert_eq!(item.status.as_deref(), Some(workflow.initial.as_str()));
+    assert!(!item.completed);
+    assert_eq!(item.completed_at, None);
+    assert_eq!(item.updated_at, later);
+    assert_eq!(item.status_history.len(), 2);
+  }
+
+  #[test]
+  fn set_completed_without_change_is_a_no_op() {
+    let workflow = WorkflowSetting::default();
+    let mut item = todo(Some("done"), true);
+    set_completed(&workflow, &mut item, true, NOW).unwrap();
+    assert_eq!(item.completed_at.as_deref(), Some("2026-03-01T08:00:00Z"));
+    assert!(item.status_history.is_empty());
+  }
+
+  #[test]
+  fn set_completed_respects_transition_rules() {
+    let workflow = WorkflowSetting::default();
+    // 阻塞中的待办不能直接完成。
+    let mut item = todo(Some("blocked"), false);
+    assert_eq!(
+      invalid_field(set_completed(&workflow, &mut item, true, NOW)).as_deref(),
+      Some("status")
+    );
+    assert!(!item.completed);
+    assert_eq!(item.completed_at, None);
+
+    // 只能回到初始状态的完成状态不存在这条切换时，取消完成同样被拒绝。
+    let mut workflow = WorkflowSetting::default();
+    workflow.statuses[4].transitions = vec!["backlog".to_string()];
+    let mut item = todo(Some("done"), true);
+    assert_eq!(
+      invalid_field(set_completed(&workflow, &mut item, false, NOW)).as_deref(),
+      Some("status")
+    );
+    assert!(item.completed);
+  }
+}
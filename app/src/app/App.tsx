@@ -8,6 +8,7 @@ import { usePomodoroStore } from './stores/pomodoroStore'
 import { useSettingsStore } from './stores/settingsStore'
 import { useTodoStore } from './stores/todoStore'
 import { useWindowStateSync } from './hooks/useWindowStateSync'
+import { errorMessage } from './services/api'
 import { ensureNotificationPermission } from './utils/notifications'
 import type {
   PomodoroConfig,
@@ -349,7 +350,7 @@ const handleModalSubmit = async (form: TodoFormValues) => {
     }
     handleModalClose()
   } catch (error) {
-    alert('保存待办失败：' + (error instanceof Error ? error.message : String(error)))
+    alert('保存待办失败：' + errorMessage(error))
   } finally {
     setModalSaving(false)
   }
@@ -365,7 +366,7 @@ const handleModalDelete = async () => {
     await removeTodo(activeTodo.id)
     handleModalClose()
   } catch (error) {
-    alert('删除待办失败：' + (error instanceof Error ? error.message : String(error)))
+    alert('删除待办失败：' + errorMessage(error))
   } finally {
     setModalDeleting(false)
   }
@@ -430,14 +431,10 @@ const handleResetFilters = () => {
   const handleOpacityChange = async (value: number) => {
     setAdjustingOpacity(true)
     try {
+      // 保存后由后端通知悬浮窗更新透明度
       await updateFloatingOpacity(value)
-      // 通过 Tauri 事件通知悬浮窗更新透明度
-      if (isTauri) {
-        const { emit } = await import('@tauri-apps/api/event')
-        await emit('opacity-changed', { opacity: value })
-      }
     } catch (error) {
-      alert('更新透明度失败：' + (error instanceof Error ? error.message : String(error)))
+      alert('更新透明度失败：' + errorMessage(error))
     } finally {
       setAdjustingOpacity(false)
     }
@@ -98,7 +98,17 @@ const applyThemeOpacity = (opacity: number) => {
 
 
 
-export const FloatingApp = () => {
+interface FloatingAppProps {
+
+  /** 项目悬浮窗只显示该项目的待办，新建的待办也归入该项目 */
+
+  project?: string | null
+
+}
+
+
+
+export const FloatingApp = ({ project }: FloatingAppProps) => {
 
   useWindowStateSync()
 
@@ -182,7 +192,8 @@ export const FloatingApp = () => {
 
       const { listen } = await import('@tauri-apps/api/event')
 
-      unlisten = await listen<{ opacity: number }>('opacity-changed', (event) => {
+      // 后端保存设置后按新设置广播悬浮窗表现
+      unlisten = await listen<{ opacity: number }>('window:behavior', (event) => {
 
         applyThemeOpacity(event.payload.opacity)
 
@@ -248,21 +259,37 @@ export const FloatingApp = () => {
 
 
 
+  const scopedTodos = useMemo(
+
+    () =>
+
+      project
+
+        ? todos.filter((todo) => (todo.project ?? '').trim() === project)
+
+        : todos,
+
+    [todos, project],
+
+  )
+
+
+
   const activeTodos = useMemo(() => {
 
-    return [...todos]
+    return [...scopedTodos]
 
       .filter((todo) => !todo.completed)
 
       .sort((a, b) => dayjs(b.createdAt).valueOf() - dayjs(a.createdAt).valueOf())
 
-  }, [todos])
+  }, [scopedTodos])
 
 
 
   const completedTodos = useMemo(() => {
 
-    return [...todos]
+    return [...scopedTodos]
 
       .filter((todo) => todo.completed)
 
@@ -274,7 +301,7 @@ export const FloatingApp = () => {
 
       )
 
-  }, [todos])
+  }, [scopedTodos])
 
 
 
@@ -282,7 +309,7 @@ export const FloatingApp = () => {
 
     () =>
 
-      todos.filter(
+      scopedTodos.filter(
 
         (todo) =>
 
@@ -294,7 +321,7 @@ export const FloatingApp = () => {
 
       ).length,
 
-    [todos],
+    [scopedTodos],
 
   )
 
@@ -350,6 +377,8 @@ export const FloatingApp = () => {
 
         tags: [],
 
+        project: project ?? null,
+
       })
 
       setQuickTitle('')
@@ -480,7 +509,7 @@ export const FloatingApp = () => {
 
       <header className="floating-header">
 
-        <span className="floating-title">专注待办 · 悬浮窗</span>
+        <span className="floating-title">专注待办 · {project ?? '悬浮窗'}</span>
 
                     <div className="floating-actions">
         <button
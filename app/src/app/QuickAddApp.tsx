@@ -0,0 +1,105 @@
+import type { FormEvent, KeyboardEvent } from 'react'
+import { useEffect, useRef, useState } from 'react'
+
+import { errorMessage } from './services/api'
+import { useTodoStore } from './stores/todoStore'
+import type { TodoPriority } from './types'
+
+const isTauri =
+  typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window
+
+const priorityOptions: Array<{ label: string; value: TodoPriority }> = [
+  { label: '低', value: 'low' },
+  { label: '中', value: 'medium' },
+  { label: '高', value: 'high' },
+]
+
+const closeWindow = async () => {
+  if (!isTauri) return
+  try {
+    const { getCurrentWebviewWindow } = await import('@tauri-apps/api/webviewWindow')
+    await getCurrentWebviewWindow().close()
+  } catch {
+    // 忽略关闭失败，窗口失去焦点时后端也会关闭它
+  }
+}
+
+/** 快速新建弹窗：回车保存并关闭，Esc 直接关闭 */
+export const QuickAddApp = () => {
+  const addTodo = useTodoStore((state) => state.addTodo)
+
+  const [title, setTitle] = useState('')
+  const [priority, setPriority] = useState<TodoPriority>('medium')
+  const [submitting, setSubmitting] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+  const inputRef = useRef<HTMLInputElement>(null)
+
+  useEffect(() => {
+    inputRef.current?.focus()
+  }, [])
+
+  const handleSubmit = async (event: FormEvent<HTMLFormElement>) => {
+    event.preventDefault()
+    const trimmed = title.trim()
+    if (!trimmed) {
+      setError('请输入任务标题')
+      return
+    }
+    setError(null)
+    setSubmitting(true)
+    try {
+      await addTodo({ title: trimmed, priority, tags: [] })
+      await closeWindow()
+    } catch (error) {
+      setError(errorMessage(error))
+    } finally {
+      setSubmitting(false)
+    }
+  }
+
+  const handleKeyDown = (event: KeyboardEvent<HTMLFormElement>) => {
+    if (event.key === 'Escape') {
+      event.preventDefault()
+      void closeWindow()
+    }
+  }
+
+  return (
+    <form
+      className="quick-add-shell"
+      data-tauri-drag-region
+      onSubmit={handleSubmit}
+      onKeyDown={handleKeyDown}
+    >
+      <div className="quick-add-row">
+        <input
+          ref={inputRef}
+          className="floating-quick-input"
+          placeholder="输入待办标题，回车保存"
+          value={title}
+          onChange={(event) => {
+            setTitle(event.target.value)
+            if (error) setError(null)
+          }}
+          disabled={submitting}
+        />
+        <select
+          className="quick-add-priority"
+          value={priority}
+          onChange={(event) => setPriority(event.target.value as TodoPriority)}
+          disabled={submitting}
+        >
+          {priorityOptions.map((option) => (
+            <option key={option.value} value={option.value}>
+              {option.label}
+            </option>
+          ))}
+        </select>
+        <button type="submit" className="floating-quick-button" disabled={submitting}>
+          {submitting ? '正在添加…' : '添加'}
+        </button>
+      </div>
+      {error ? <p className="floating-quick-error">{error}</p> : null}
+    </form>
+  )
+}
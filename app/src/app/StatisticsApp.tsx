@@ -0,0 +1,124 @@
+import dayjs from 'dayjs'
+import { useEffect, useMemo, useState } from 'react'
+
+import { errorMessage, fetchFocusStats } from './services/api'
+import type { FocusStats } from './types'
+
+const rangeOptions: Array<{ label: string; days: number }> = [
+  { label: '近 7 天', days: 7 },
+  { label: '近 30 天', days: 30 },
+  { label: '近 90 天', days: 90 },
+]
+
+const formatMinutes = (minutes: number) => {
+  if (minutes < 60) return `${minutes} 分钟`
+  const hours = Math.floor(minutes / 60)
+  const rest = minutes % 60
+  return rest ? `${hours} 小时 ${rest} 分钟` : `${hours} 小时`
+}
+
+/** 统计窗口：按时间范围汇总专注数据，并按天列出专注时长 */
+export const StatisticsApp = () => {
+  const [days, setDays] = useState(rangeOptions[0].days)
+  const [stats, setStats] = useState<FocusStats | null>(null)
+  const [loading, setLoading] = useState(false)
+  const [error, setError] = useState<string | null>(null)
+
+  useEffect(() => {
+    let cancelled = false
+    const to = dayjs()
+    const from = to.subtract(days - 1, 'day')
+    setLoading(true)
+    fetchFocusStats(from.format('YYYY-MM-DD'), to.format('YYYY-MM-DD'))
+      .then((data) => {
+        if (cancelled) return
+        setStats(data)
+        setError(null)
+      })
+      .catch((error) => {
+        if (!cancelled) setError(errorMessage(error))
+      })
+      .finally(() => {
+        if (!cancelled) setLoading(false)
+      })
+    return () => {
+      cancelled = true
+    }
+  }, [days])
+
+  const maxMinutes = useMemo(
+    () => Math.max(1, ...(stats?.days ?? []).map((day) => day.focusMinutes)),
+    [stats],
+  )
+
+  return (
+    <div className="app-shell statistics-shell">
+      <section className="app-section">
+        <header className="section-header">
+          <div>
+            <h2>专注统计</h2>
+            <p>{loading ? '正在加载…' : '完成的番茄与待办按本地日期汇总。'}</p>
+          </div>
+          <div className="section-actions">
+            {rangeOptions.map((option) => (
+              <button
+                key={option.days}
+                type="button"
+                className={option.days === days ? 'primary-button' : 'ghost-button'}
+                onClick={() => setDays(option.days)}
+              >
+                {option.label}
+              </button>
+            ))}
+          </div>
+        </header>
+
+        {error ? <p className="app-error">{error}</p> : null}
+
+        {stats ? (
+          <>
+            <div className="config-grid config-grid-stats">
+              <div className="config-card">
+                <span className="config-label">专注时长</span>
+                <strong>{formatMinutes(stats.totalFocusMinutes)}</strong>
+              </div>
+              <div className="config-card">
+                <span className="config-label">专注完成次数</span>
+                <strong>{stats.focusSessions} 次</strong>
+              </div>
+              <div className="config-card">
+                <span className="config-label">中断次数</span>
+                <strong>{stats.interruptedSessions} 次</strong>
+              </div>
+              <div className="config-card">
+                <span className="config-label">完成待办</span>
+                <strong>{stats.completedTodos} 项</strong>
+              </div>
+            </div>
+
+            <h3 className="config-subtitle">每日专注</h3>
+            {stats.days.length === 0 ? (
+              <p className="meta-text">这段时间还没有完成的专注。</p>
+            ) : (
+              <ul className="statistics-days">
+                {stats.days.map((day) => (
+                  <li key={day.date}>
+                    <span className="meta-text">{dayjs(day.date).format('MM/DD')}</span>
+                    <span className="statistics-bar">
+                      <span
+                        style={{ width: `${(day.focusMinutes / maxMinutes) * 100}%` }}
+                      />
+                    </span>
+                    <span className="meta-text">
+                      {formatMinutes(day.focusMinutes)} · {day.focusSessions} 次
+                    </span>
+                  </li>
+                ))}
+              </ul>
+            )}
+          </>
+        ) : null}
+      </section>
+    </div>
+  )
+}
@@ -1,96 +1,43 @@
 import type { CloseRequestedEvent } from '@tauri-apps/api/window'
 import { useEffect } from 'react'
 
-import { useSettingsStore } from '../stores/settingsStore'
+import { closeWindow, windowKindOf } from '../services/api'
 
 const isTauri =
   typeof window !== 'undefined' && '__TAURI_INTERNALS__' in window
 
+// 窗口几何信息由后端在移动、缩放后防抖保存，这里只把关闭请求按窗口类型交给后端处理。
 export const useWindowStateSync = () => {
-  const persistWindowGeometry = useSettingsStore(
-    (state) => state.persistWindowGeometry,
-  )
-
   useEffect(() => {
     if (!isTauri) return
 
     let disposed = false
-    let rafHandle: number | null = null
     const unlistenList: Array<() => void> = []
 
     const setup = async () => {
       try {
         const windowModule = await import('@tauri-apps/api/window')
-        const eventModule = await import('@tauri-apps/api/event')
 
         if (disposed) return
 
         const currentWindow = windowModule.getCurrentWindow()
-        const label = currentWindow.label
-        if (label !== 'main' && label !== 'floating') {
-          return
-        }
-        const windowLabel = label as 'main' | 'floating'
-
-        const readGeometry = async () => {
-          try {
-            const [position, size] = await Promise.all([
-              currentWindow.outerPosition(),
-              currentWindow.outerSize(),
-            ])
-            return {
-              x: position.x,
-              y: position.y,
-              width: size.width,
-              height: size.height,
-            }
-          } catch {
-            return {}
-          }
-        }
-
-        const persist = async () => {
-          try {
-            const geometry = await readGeometry()
-            await persistWindowGeometry(windowLabel, geometry)
-          } catch {
-            // 忽略持久化失败，继续使用内存状态
-          }
-        }
-
-        const schedulePersist = () => {
-          if (rafHandle !== null) {
-            cancelAnimationFrame(rafHandle)
-          }
-          rafHandle = requestAnimationFrame(() => {
-            void persist()
-          })
-        }
-
-        unlistenList.push(
-          await currentWindow.listen(
-            eventModule.TauriEvent.WINDOW_MOVED,
-            schedulePersist,
-          ),
-        )
-
-        unlistenList.push(
-          await currentWindow.listen(
-            eventModule.TauriEvent.WINDOW_RESIZED,
-            schedulePersist,
-          ),
-        )
+        const kind = windowKindOf(currentWindow.label)
+        if (kind === null) return
+        const project = new URLSearchParams(window.location.search).get('project')
 
         unlistenList.push(
           await currentWindow.onCloseRequested(
             async (event: CloseRequestedEvent) => {
               event.preventDefault()
-              await persist()
 
+              // 主窗口的关闭行为（隐藏到托盘/最小化/退出）由后端按设置处理
+              if (kind === 'main') return
+
+              // 悬浮窗只隐藏，按需创建的窗口先保存几何信息再销毁
               try {
-                await currentWindow.hide()
+                await closeWindow(kind, project)
               } catch {
-                // 忽略隐藏窗口失败
+                // 忽略关闭窗口失败
               }
             },
           ),
@@ -104,9 +51,6 @@ export const useWindowStateSync = () => {
 
     return () => {
       disposed = true
-      if (rafHandle !== null) {
-        cancelAnimationFrame(rafHandle)
-      }
       unlistenList.forEach((unlisten) => {
         try {
           unlisten()
@@ -115,6 +59,5 @@ export const useWindowStateSync = () => {
         }
       })
     }
-  }, [persistWindowGeometry])
+  }, [])
 }
-
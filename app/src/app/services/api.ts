@@ -1,15 +1,30 @@
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 
 import type {
+  CommandError,
+  FocusStats,
   PomodoroConfig,
   PomodoroSession,
   PomodoroSessionDraft,
+  PomodoroSessionKind,
+  SettingsPatch,
   TodoDraft,
+  TimerSnapshot,
   TodoItem,
   UserSettings,
-  WindowGeometry,
+  WindowKind,
 } from '../types'
 
+const isCommandError = (error: unknown): error is CommandError =>
+  typeof error === 'object' &&
+  error !== null &&
+  typeof (error as CommandError).message === 'string' &&
+  typeof (error as CommandError).code === 'string'
+
+export const errorMessage = (error: unknown) =>
+  error instanceof Error || isCommandError(error) ? error.message : String(error)
+
 export const listTodos = () => invoke<TodoItem[]>('list_todos')
 
 export const createTodo = (draft: TodoDraft) =>
@@ -38,10 +53,41 @@ export const listPomodoroSessions = (date?: string) =>
     date ? { date } : {},
   )
 
+export const fetchFocusStats = (from?: string, to?: string) =>
+  invoke<FocusStats>('get_focus_stats', { from, to })
+
+export const fetchTimerState = () => invoke<TimerSnapshot>('get_timer_state')
+
+export const startTimer = (phase?: PomodoroSessionKind) =>
+  invoke<TimerSnapshot>('start_timer', phase ? { phase } : {})
+
+export const pauseTimer = () => invoke<TimerSnapshot>('pause_timer')
+
+export const resetTimer = (phase?: PomodoroSessionKind) =>
+  invoke<TimerSnapshot>('reset_timer', phase ? { phase } : {})
+
+export const onTimerChanged = (handler: (snapshot: TimerSnapshot) => void) =>
+  listen<TimerSnapshot>('pomodoro:timer', (event) => handler(event.payload))
+
+export const onSessionRecorded = (handler: (session: PomodoroSession) => void) =>
+  listen<PomodoroSession>('pomodoro:session', (event) => handler(event.payload))
+
 export const fetchSettings = () => invoke<UserSettings>('get_settings')
 
-export const saveSettings = (settings: UserSettings) =>
-  invoke<UserSettings>('save_settings', { settings })
+export const patchSettings = (patch: SettingsPatch) =>
+  invoke<UserSettings>('patch_settings', { patch })
+
+export const onSettingsChanged = (handler: (keys: string[]) => void) =>
+  listen<{ keys: string[] }>('settings:changed', (event) => handler(event.payload.keys))
+
+/** 按窗口标签推断窗口类型；标签规则与后端 `WindowKind::label` 一致 */
+export const windowKindOf = (label: string): WindowKind | null => {
+  if (label === 'main' || label === 'floating') return label
+  if (label === 'quick-add') return 'quickAdd'
+  if (label === 'statistics') return 'statistics'
+  if (label.startsWith('project-')) return 'projectFloating'
+  return null
+}
 
-export const recordWindowState = (label: string, geometry: WindowGeometry) =>
-  invoke<UserSettings>('record_window_state', { label, geometry })
+export const closeWindow = (kind: WindowKind, project?: string | null) =>
+  invoke<void>('close_window', project ? { kind, project } : { kind })
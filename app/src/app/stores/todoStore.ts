@@ -3,6 +3,7 @@ import { create } from 'zustand'
 import {
   createTodo,
   deleteTodo,
+  errorMessage,
   listTodos,
   toggleTodo,
   updateTodo,
@@ -27,9 +28,6 @@ interface TodoState {
   clearError: () => void
 }
 
-const toMessage = (error: unknown) =>
-  error instanceof Error ? error.message : String(error)
-
 export const useTodoStore = create<TodoState>((set) => ({
   todos: [],
   loading: false,
@@ -40,7 +38,7 @@ export const useTodoStore = create<TodoState>((set) => ({
       const data = await listTodos()
       set({ todos: data, loading: false })
     } catch (error) {
-      set({ loading: false, error: toMessage(error) })
+      set({ loading: false, error: errorMessage(error) })
     }
   },
   async addTodo(draft) {
@@ -56,7 +54,7 @@ export const useTodoStore = create<TodoState>((set) => ({
       }))
       return todo
     } catch (error) {
-      set({ error: toMessage(error) })
+      set({ error: errorMessage(error) })
       throw error
     }
   },
@@ -70,7 +68,7 @@ export const useTodoStore = create<TodoState>((set) => ({
       }))
       return updated
     } catch (error) {
-      set({ error: toMessage(error) })
+      set({ error: errorMessage(error) })
       throw error
     }
   },
@@ -81,7 +79,7 @@ export const useTodoStore = create<TodoState>((set) => ({
         todos: state.todos.filter((item) => item.id !== id),
       }))
     } catch (error) {
-      set({ error: toMessage(error) })
+      set({ error: errorMessage(error) })
       throw error
     }
   },
@@ -95,7 +93,7 @@ export const useTodoStore = create<TodoState>((set) => ({
       }))
       return next
     } catch (error) {
-      set({ error: toMessage(error) })
+      set({ error: errorMessage(error) })
       throw error
     }
   },
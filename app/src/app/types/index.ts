@@ -6,6 +6,7 @@ export interface TodoItem {
   detail?: string | null
   priority: TodoPriority
   tags: string[]
+  project?: string | null
   plannedAt?: string | null
   dueAt?: string | null
   completed: boolean
@@ -19,6 +20,7 @@ export interface TodoDraft {
   detail?: string | null
   priority: TodoPriority
   tags: string[]
+  project?: string | null
   plannedAt?: string | null
   dueAt?: string | null
 }
@@ -52,6 +54,33 @@ export interface PomodoroSessionDraft {
   completed: boolean
 }
 
+export interface DailyFocus {
+  date: string
+  focusMinutes: number
+  focusSessions: number
+}
+
+export interface FocusStats {
+  totalFocusMinutes: number
+  focusSessions: number
+  breakSessions: number
+  interruptedSessions: number
+  completedTodos: number
+  days: DailyFocus[]
+}
+
+export type TimerStatus = 'idle' | 'running' | 'paused'
+
+export interface TimerSnapshot {
+  status: TimerStatus
+  phase: PomodoroSessionKind
+  durationSeconds: number
+  remainingSeconds: number
+  startedAt?: string | null
+  todoId?: string | null
+  focusCount: number
+}
+
 export type ThemeMode = 'system' | 'light' | 'dark' | 'mac'
 
 export interface HotkeySetting {
@@ -81,3 +110,33 @@ export interface UserSettings {
   hotkeys: HotkeySetting
   windowState: WindowState
 }
+
+/** 设置的局部更新，按 JSON Merge Patch 合并，`null` 把字段恢复为默认值 */
+export type SettingsPatch = {
+  [K in keyof UserSettings]?:
+    | (UserSettings[K] extends object ? Partial<UserSettings[K]> : UserSettings[K])
+    | null
+}
+
+export type CommandErrorCode =
+  | 'not_found'
+  | 'validation'
+  | 'io'
+  | 'corrupt_data'
+  | 'window'
+  | 'internal'
+
+export interface CommandError {
+  code: CommandErrorCode
+  message: string
+  field?: string
+  details?: Record<string, unknown>
+}
+
+/** 与后端 `WindowKind` 对应的窗口类型 */
+export type WindowKind =
+  | 'main'
+  | 'floating'
+  | 'quickAdd'
+  | 'statistics'
+  | 'projectFloating'
@@ -53,14 +53,7 @@ export const ensureNotificationPermission = async (): Promise<boolean> => {
   }
 }
 
-export const notifyPomodoroCompletion = async (
-  title: string,
-  body: string,
-) => {
+export const playCompletionChime = () => {
   if (!isTauri) return
-  const { sendNotification } = await import('@tauri-apps/plugin-notification')
-  const granted = await ensureNotificationPermission()
-  if (!granted) return
-  await sendNotification({ title, body })
   playChime()
 }
@@ -3,6 +3,8 @@ import ReactDOM from 'react-dom/client'
 
 import { App } from './app'
 import { FloatingApp } from './app/FloatingApp'
+import { QuickAddApp } from './app/QuickAddApp'
+import { StatisticsApp } from './app/StatisticsApp'
 import './app/styles/theme.css'
 import './app/styles/global.css'
 import './app/styles/pomodoro.css'
@@ -30,12 +32,24 @@ const bootstrap = async () => {
   document.body.dataset.window = label
   document.documentElement.dataset.window = label
 
-  const RootComponent = label === 'floating' ? FloatingApp : App
+  // 按需创建的窗口通过 `?window=` 指定界面，项目悬浮窗另带 `&project=`
+  const params = new URLSearchParams(window.location.search)
+  const route = params.get('window')
+  const project = params.get('project')
+
+  let root: React.ReactNode
+  if (route === 'quick-add') {
+    root = <QuickAddApp />
+  } else if (route === 'statistics') {
+    root = <StatisticsApp />
+  } else if (label === 'floating' || label.startsWith('project-')) {
+    root = <FloatingApp project={project} />
+  } else {
+    root = <App />
+  }
 
   ReactDOM.createRoot(rootElement).render(
-    <React.StrictMode>
-      <RootComponent />
-    </React.StrictMode>,
+    <React.StrictMode>{root}</React.StrictMode>,
   )
 }
 